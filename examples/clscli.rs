@@ -1,4 +1,4 @@
-use clearscreen::ClearScreen;
+use clearscreen::{ClearScreen, TerminfoEntry};
 use std::{env, thread::sleep, time::Duration};
 use thiserror::Error;
 
@@ -10,6 +10,7 @@ fn main() -> Result<(), Error> {
 			"TerminfoScreen" => ClearScreen::TerminfoScreen,
 			"TerminfoScrollback" => ClearScreen::TerminfoScrollback,
 			"TerminfoReset" => ClearScreen::TerminfoReset,
+			"TerminfoRecover" => ClearScreen::TerminfoRecover,
 			"XtermClear" => ClearScreen::XtermClear,
 			"XtermReset" => ClearScreen::XtermReset,
 			"TputClear" => ClearScreen::TputClear,
@@ -24,18 +25,28 @@ fn main() -> Result<(), Error> {
 			"WindowsCooked" => ClearScreen::WindowsCooked,
 			"VtRis" => ClearScreen::VtRis,
 			"VtLeaveAlt" => ClearScreen::VtLeaveAlt,
+			"VtRecover" => ClearScreen::VtRecover,
 			"VtCooked" => ClearScreen::VtCooked,
 			"VtWellDone" => ClearScreen::VtWellDone,
+			cap if cap.starts_with("TerminfoCapability:") => ClearScreen::TerminfoCapability(
+				String::from(&cap["TerminfoCapability:".len()..]).leak(),
+			),
 			_ => return Err(Error::UnknownVariant(variant)),
 		};
 
 		println!("variant = {:?}, sleeping 1 second", cs);
+		if let Ok(entry) = TerminfoEntry::from_env() {
+			println!(
+				"terminfo entry = {} (for TERM={}) from {}",
+				entry.entry, entry.term, entry.source
+			);
+		}
 		sleep(Duration::from_secs(1));
 		cs.clear()?;
 
 		Ok(())
 	} else {
-		println!("Usage: cargo run --example cli -- <variant>\nWhere <variant> is one of the ClearScreen enum variants, same casing, or 'auto'.\nI recommend piping into `hexdump -C` to see what’s happening.");
+		println!("Usage: cargo run --example cli -- <variant>\nWhere <variant> is one of the ClearScreen enum variants, same casing, 'TerminfoCapability:<name>', or 'auto'.\nI recommend piping into `hexdump -C` to see what’s happening.");
 		Ok(())
 	}
 }
@@ -0,0 +1,377 @@
+use std::{
+	fmt,
+	io::Write,
+	ops::{BitAnd, BitOr, BitOrAssign, Sub},
+};
+
+use terminfo::expand::Context;
+
+#[cfg(unix)]
+use super::TermiosProfile;
+use super::{command, info, plan, sequences, ClearScreen, ConsoleCall, Error, Options, Plan, Step};
+
+/// Independent actions to clear or restore a terminal, which can be combined.
+///
+/// Combine actions with `|`, and perform them with a [`Backend`] through
+/// [`ClearScreen::Actions`]. Most of the other [`ClearScreen`] variants are such combinations; see
+/// [`ClearScreen::actions()`].
+///
+/// Whatever order they’re given in, actions are performed in the order of the constants below.
+///
+/// ```no_run
+/// # use clearscreen::{Backend, ClearActions, ClearScreen};
+/// ClearScreen::Actions(
+///     ClearActions::LEAVE_ALT_SCREEN | ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN,
+///     Backend::Terminfo,
+/// )
+/// .clear()?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ClearActions(u8);
+
+impl ClearActions {
+	/// Restores the terminal’s line discipline to sane settings.
+	///
+	/// On Unix, this is [`VtWellDone`][ClearScreen::VtWellDone], and on Windows,
+	/// [`WindowsCooked`][ClearScreen::WindowsCooked], whatever the backend.
+	pub const RESTORE_TERMIOS: Self = Self(1 << 0);
+
+	/// Resets the terminal to its initial state.
+	///
+	/// This is [`TerminfoReset`][ClearScreen::TerminfoReset],
+	/// [`XtermReset`][ClearScreen::XtermReset], or [`TputReset`][ClearScreen::TputReset].
+	pub const FULL_RESET: Self = Self(1 << 1);
+
+	/// Leaves the alternate screen.
+	///
+	/// This is the **rmcup** capability, or [`VtLeaveAlt`][ClearScreen::VtLeaveAlt].
+	pub const LEAVE_ALT_SCREEN: Self = Self(1 << 2);
+
+	/// Resets the modes commonly left behind by crashed TUI applications.
+	///
+	/// This is [`TerminfoRecover`][ClearScreen::TerminfoRecover] or
+	/// [`VtRecover`][ClearScreen::VtRecover].
+	pub const RESET_MODES: Self = Self(1 << 3);
+
+	/// Moves the cursor to the top left corner.
+	pub const HOME_CURSOR: Self = Self(1 << 4);
+
+	/// Erases the screen.
+	///
+	/// With the terminfo and tput backends, this also moves the cursor to the top left corner, as
+	/// the **clear** capability does.
+	pub const ERASE_SCREEN: Self = Self(1 << 5);
+
+	/// Erases the scrollback.
+	///
+	/// With the terminfo backend, this is the **E3** capability. If the screen is erased too, it’s
+	/// skipped when the terminal doesn’t have it, as the `clear` command does.
+	pub const ERASE_SCROLLBACK: Self = Self(1 << 6);
+
+	const NAMES: [(Self, &'static str); 7] = [
+		(Self::RESTORE_TERMIOS, "RESTORE_TERMIOS"),
+		(Self::FULL_RESET, "FULL_RESET"),
+		(Self::LEAVE_ALT_SCREEN, "LEAVE_ALT_SCREEN"),
+		(Self::RESET_MODES, "RESET_MODES"),
+		(Self::HOME_CURSOR, "HOME_CURSOR"),
+		(Self::ERASE_SCREEN, "ERASE_SCREEN"),
+		(Self::ERASE_SCROLLBACK, "ERASE_SCROLLBACK"),
+	];
+
+	/// No actions.
+	pub const fn empty() -> Self {
+		Self(0)
+	}
+
+	/// Whether there are no actions.
+	pub const fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+
+	/// Whether all of the given actions are in these.
+	pub const fn contains(self, other: Self) -> bool {
+		self.0 & other.0 == other.0
+	}
+
+	/// Both these actions and the given ones.
+	pub const fn union(self, other: Self) -> Self {
+		Self(self.0 | other.0)
+	}
+
+	/// Performs the actions with a backend, printing to a given writer.
+	pub(crate) fn perform(
+		self,
+		backend: Backend,
+		w: &mut impl Write,
+		options: &Options,
+	) -> Result<(), Error> {
+		let mut plan = Plan::default();
+		self.plan(backend, options, &mut plan)?;
+		plan.execute_to_with(w, options)
+	}
+
+	/// Plans the actions with a backend.
+	///
+	/// The actions which the backend can’t do are checked for first: then, nothing is planned, and
+	/// [`Error::UnsupportedActions`] is returned.
+	pub(crate) fn plan(
+		self,
+		backend: Backend,
+		options: &Options,
+		plan: &mut Plan,
+	) -> Result<(), Error> {
+		let unsupported = backend.unsupported(self);
+		if !unsupported.is_empty() {
+			return Err(Error::UnsupportedActions {
+				actions: unsupported,
+				backend,
+			});
+		}
+
+		if self.contains(Self::RESTORE_TERMIOS) {
+			#[cfg(unix)]
+			plan.push(Step::Termios(TermiosProfile::well_done().resolve(options)));
+			plan::console(plan, ConsoleCall::Cooked);
+		}
+
+		let clear = self - Self::RESTORE_TERMIOS;
+		if clear.is_empty() {
+			return Ok(());
+		}
+
+		match backend {
+			Backend::Terminfo => clear.terminfo(&mut plan.record(), options),
+			Backend::Xterm => clear.xterm(&mut plan.record(), options),
+			Backend::Tput => clear.tput(options, plan),
+			Backend::WindowsConsole => {
+				#[cfg(feature = "windows-console")]
+				if clear.contains(Self::ERASE_SCREEN) {
+					plan::console(plan, ConsoleCall::Clear);
+				}
+				Ok(())
+			}
+		}
+	}
+
+	fn terminfo(self, w: &mut impl info::Output, options: &Options) -> Result<(), Error> {
+		let info = info::load(options)?;
+		if self.contains(Self::FULL_RESET) {
+			info::reset(&info, options, w)?;
+		}
+		if self.contains(Self::LEAVE_ALT_SCREEN) {
+			let delays = info::Delays::new(&info, options, 1);
+			if !info::write_cap(&info, "rmcup", &mut Context::default(), &delays, w)? {
+				return Err(info.missing("rmcup"));
+			}
+		}
+		if self.contains(Self::RESET_MODES) {
+			let skip: &[&str] = if self.contains(Self::LEAVE_ALT_SCREEN) {
+				&["rmcup"]
+			} else {
+				&[]
+			};
+			info::recover(&info, options, skip, w)?;
+		}
+
+		let delays = info::Delays::new(&info, options, info::lines(&info));
+		let mut ctx = Context::default();
+		let erase = self.contains(Self::ERASE_SCREEN);
+		if self.contains(Self::HOME_CURSOR)
+			&& !erase && !info::write_cap(&info, "home", &mut ctx, &delays, w)?
+		{
+			return Err(info.missing("home"));
+		}
+		if erase && !info::write_cap(&info, "clear", &mut ctx, &delays, w)? {
+			return Err(info.missing("clear"));
+		}
+		if self.contains(Self::ERASE_SCROLLBACK)
+			&& !info::write_cap(&info, "E3", &mut ctx, &delays, w)?
+			&& !erase
+		{
+			return Err(info.missing("E3"));
+		}
+
+		w.flush()?;
+		Ok(())
+	}
+
+	fn xterm(self, w: &mut impl Write, options: &Options) -> Result<(), Error> {
+		if self.contains(Self::FULL_RESET) {
+			ClearScreen::XtermReset.clear_to_with(w, options)?;
+		}
+		if self.contains(Self::LEAVE_ALT_SCREEN) {
+			ClearScreen::VtLeaveAlt.clear_to_with(w, options)?;
+		}
+		if self.contains(Self::RESET_MODES) {
+			if self.contains(Self::LEAVE_ALT_SCREEN) {
+				let recover = sequences::VT_RECOVER.as_str();
+				let recover = recover.replacen(sequences::LEAVE_ALT_SCREEN.as_str(), "", 1);
+				w.write_all(recover.as_bytes())?;
+			} else {
+				ClearScreen::VtRecover.clear_to_with(w, options)?;
+			}
+		}
+
+		for (action, seq) in [
+			(Self::HOME_CURSOR, sequences::CURSOR_HOME),
+			(Self::ERASE_SCREEN, sequences::ERASE_SCREEN),
+			(Self::ERASE_SCROLLBACK, sequences::ERASE_SCROLLBACK),
+		] {
+			if self.contains(action) {
+				w.write_all(seq.as_bytes())?;
+			}
+		}
+
+		w.flush()?;
+		Ok(())
+	}
+
+	fn tput(self, options: &Options, plan: &mut Plan) -> Result<(), Error> {
+		let mut tput = |args: &[&str]| -> Result<(), Error> {
+			plan.push(command::tput_step(args, options)?);
+			Ok(())
+		};
+
+		if self.contains(Self::FULL_RESET) {
+			tput(&["reset"])?;
+		}
+		if self.contains(Self::LEAVE_ALT_SCREEN) {
+			tput(&["rmcup"])?;
+		}
+
+		// `tput clear` erases the scrollback too, unless given `-x`
+		let erase = self.contains(Self::ERASE_SCREEN);
+		let scrollback = self.contains(Self::ERASE_SCROLLBACK);
+		if erase && scrollback {
+			tput(&["clear"])?;
+		} else if erase {
+			tput(&["-x", "clear"])?;
+		} else {
+			if self.contains(Self::HOME_CURSOR) {
+				tput(&["home"])?;
+			}
+			if scrollback {
+				tput(&["E3"])?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl BitOr for ClearActions {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self {
+		self.union(rhs)
+	}
+}
+
+impl BitOrAssign for ClearActions {
+	fn bitor_assign(&mut self, rhs: Self) {
+		*self = self.union(rhs);
+	}
+}
+
+impl BitAnd for ClearActions {
+	type Output = Self;
+
+	fn bitand(self, rhs: Self) -> Self {
+		Self(self.0 & rhs.0)
+	}
+}
+
+impl Sub for ClearActions {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self {
+		Self(self.0 & !rhs.0)
+	}
+}
+
+/// Shows the names of the actions, separated by ` | `, or `(none)`.
+impl fmt::Display for ClearActions {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.is_empty() {
+			return write!(f, "(none)");
+		}
+
+		let mut names = Self::NAMES
+			.iter()
+			.filter(|(action, _)| self.contains(*action))
+			.map(|(_, name)| *name);
+		if let Some(first) = names.next() {
+			write!(f, "{first}")?;
+		}
+		for name in names {
+			write!(f, " | {name}")?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for ClearActions {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "ClearActions({self})")
+	}
+}
+
+/// How [`ClearActions`] are performed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+	/// With the sequences in the terminal’s terminfo entry, as for the terminfo variants.
+	///
+	/// All actions are supported, if the terminal has the capabilities.
+	Terminfo,
+
+	/// With fixed sequences, as xterm and most modern terminals understand them.
+	///
+	/// All actions are supported.
+	Xterm,
+
+	/// By running `tput`, as for [`TputClear`][ClearScreen::TputClear].
+	///
+	/// [`RESET_MODES`][ClearActions::RESET_MODES] isn’t supported, as `tput` can’t do it in one go.
+	Tput,
+
+	/// With Windows Console functions.
+	///
+	/// Only [`RESTORE_TERMIOS`][ClearActions::RESTORE_TERMIOS] is supported, and with the
+	/// `windows-console` feature, moving the cursor home and erasing the screen and the scrollback,
+	/// all together, as `WindowsConsoleClear` does. These do nothing on non-Windows targets.
+	WindowsConsole,
+}
+
+impl Backend {
+	/// The actions among those given which this backend can’t perform.
+	fn unsupported(self, actions: ClearActions) -> ClearActions {
+		let console =
+			ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN | ClearActions::ERASE_SCROLLBACK;
+
+		match self {
+			Self::Terminfo | Self::Xterm => ClearActions::empty(),
+			Self::Tput => actions & ClearActions::RESET_MODES,
+			// these can only be done together
+			Self::WindowsConsole
+				if cfg!(feature = "windows-console") && actions.contains(console) =>
+			{
+				actions - console - ClearActions::RESTORE_TERMIOS
+			}
+			Self::WindowsConsole => actions - ClearActions::RESTORE_TERMIOS,
+		}
+	}
+}
+
+/// Shows the name of the backend, in lowercase.
+impl fmt::Display for Backend {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Terminfo => "terminfo",
+			Self::Xterm => "xterm",
+			Self::Tput => "tput",
+			Self::WindowsConsole => "windows console",
+		})
+	}
+}
@@ -0,0 +1,365 @@
+use std::{
+	env,
+	ffi::{OsStr, OsString},
+	io::{self, Read, Write},
+	path::Path,
+	process::{Command, Stdio},
+	thread,
+	time::{Duration, Instant},
+};
+
+use super::{unix, CommandLookup, Error, Options, Plan, Step};
+
+/// How often to check on a command which has a timeout.
+const POLL: Duration = Duration::from_millis(10);
+
+/// Runs `tput` for the terminal selected in the options, or else `TERM`, copying its output to the
+/// writer unless it’s set to inherit stdout.
+pub(crate) fn tput(args: &[&str], options: &Options, w: &mut impl Write) -> Result<(), Error> {
+	run_step(tput_step(args, options)?, options, w)
+}
+
+/// Plans running `tput`, as [`tput()`] does.
+pub(crate) fn tput_step(args: &[&str], options: &Options) -> Result<Step, Error> {
+	let mut cmd = command("tput", options)?;
+	cmd.args(args);
+	Ok(external(&cmd, options))
+}
+
+/// Plans running a command for the terminal selected in the options, or else `TERM`, capturing its
+/// output unless it’s set to inherit stdout.
+fn external(cmd: &Command, options: &Options) -> Step {
+	Step::Run {
+		argv: argv(cmd),
+		term: options.term.clone().or_else(|| env::var("TERM").ok()),
+		capture: !options.inherit_stdout,
+	}
+}
+
+/// Runs `cls`, which needs the console as its stdout.
+pub(crate) fn cls(options: &Options) -> Result<(), Error> {
+	run_step(cls_step(options)?, options, &mut io::sink())
+}
+
+/// Plans running `cls`, which is refused on platforms other than Windows.
+pub(crate) fn cls_step(options: &Options) -> Result<Step, Error> {
+	if cfg!(not(windows)) {
+		return Err(rejected("cmd.exe", "it’s only run on Windows".into()));
+	}
+
+	let mut cmd = command("cmd.exe", options)?;
+	cmd.arg("/C").arg("cls");
+	Ok(Step::Run {
+		argv: argv(&cmd),
+		term: None,
+		capture: false,
+	})
+}
+
+fn run_step(step: Step, options: &Options, w: &mut impl Write) -> Result<(), Error> {
+	let mut plan = Plan::default();
+	plan.push(step);
+	plan.execute_to_with(w, options)
+}
+
+/// Runs a planned command, copying its output to the writer if it’s captured.
+pub(crate) fn execute(
+	argv: &[OsString],
+	term: Option<&str>,
+	capture: bool,
+	options: &Options,
+	w: &mut impl Write,
+) -> Result<(), Error> {
+	let (program, args) = argv.split_first().expect("a command has a program");
+	let mut cmd = Command::new(program);
+	cmd.args(args);
+	if let Some(term) = term {
+		cmd.env("TERM", term);
+	}
+
+	if capture {
+		let output = run(cmd, true, options)?;
+		w.write_all(&output)?;
+		w.flush()?;
+	} else {
+		w.flush()?;
+		run(cmd, false, options)?;
+	}
+
+	Ok(())
+}
+
+/// Runs `infocmp` to print the terminfo entry for a terminal in source form.
+pub(crate) fn infocmp(name: &str, options: &Options) -> Result<String, Error> {
+	let mut cmd = command("infocmp", options)?;
+	cmd.arg("-x").arg("-1").arg(name);
+	let output = run(cmd, true, options)?;
+	Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// A user-specified command to clear the screen.
+///
+/// This is for when none of the [`ClearScreen`][crate::ClearScreen] variants fit, and a program
+/// should be run instead, such as `setterm --clear all` or a site-specific script. It’s run like
+/// [`TputClear`][crate::ClearScreen::TputClear] is: its output is captured and written to the
+/// writer given (unless [`Options::inherit_stdout()`] is set), it’s given the terminal set with
+/// [`Options::term()`] if any, or else `TERM`, and [`Options::command_timeout()`] applies. If it
+/// fails, [`Error::Command`] has its command line and what it printed to stderr.
+///
+/// The program is looked up in `PATH` if it’s not a path, as with [`std::process::Command`]. This
+/// can be restricted with [`Options::command_lookup()`].
+///
+/// ```no_run
+/// # use clearscreen::ExternalCommand;
+/// ExternalCommand::new("/usr/local/bin/site-clear")
+///     .arg("--quiet")
+///     .clear()?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalCommand {
+	program: OsString,
+	args: Vec<OsString>,
+}
+
+impl ExternalCommand {
+	/// A command which runs the given program, without arguments.
+	pub fn new(program: impl AsRef<OsStr>) -> Self {
+		Self {
+			program: program.as_ref().into(),
+			args: Vec::new(),
+		}
+	}
+
+	/// `setterm --clear all`, which clears the screen and the scrollback (util-linux).
+	pub fn setterm() -> Self {
+		Self::new("setterm").args(["--clear", "all"])
+	}
+
+	/// ncurses’ `clear`.
+	///
+	/// With `keep_scrollback` false, this runs `clear`, which clears the screen and the scrollback.
+	/// With `keep_scrollback` true, this runs `clear -x`, which only clears the screen.
+	pub fn clear_command(keep_scrollback: bool) -> Self {
+		let cmd = Self::new("clear");
+		if keep_scrollback {
+			cmd.arg("-x")
+		} else {
+			cmd
+		}
+	}
+
+	/// Adds an argument.
+	pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+		self.args.push(arg.as_ref().into());
+		self
+	}
+
+	/// Adds several arguments.
+	pub fn args(mut self, args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+		self.args
+			.extend(args.into_iter().map(|arg| arg.as_ref().into()));
+		self
+	}
+
+	/// The program to run.
+	pub fn get_program(&self) -> &OsStr {
+		&self.program
+	}
+
+	/// The arguments given to the program.
+	pub fn get_args(&self) -> impl Iterator<Item = &OsStr> {
+		self.args.iter().map(OsString::as_os_str)
+	}
+
+	/// Runs the command, printing to stdout.
+	pub fn clear(&self) -> Result<(), Error> {
+		self.clear_with(&Options::default())
+	}
+
+	/// Runs the command with the given [`Options`], printing to stdout.
+	pub fn clear_with(&self, options: &Options) -> Result<(), Error> {
+		unix::foreground_output(options, || {
+			let mut stdout = io::stdout();
+			self.clear_to_with(&mut stdout, options)
+		})
+	}
+
+	/// Runs the command, copying its output to a given writer.
+	pub fn clear_to(&self, w: &mut impl Write) -> Result<(), Error> {
+		self.clear_to_with(w, &Options::default())
+	}
+
+	/// Runs the command with the given [`Options`], copying its output to a given writer.
+	pub fn clear_to_with(&self, w: &mut impl Write, options: &Options) -> Result<(), Error> {
+		let mut cmd = command(&self.program, options)?;
+		cmd.args(&self.args);
+		run_step(external(&cmd, options), options, w)
+	}
+}
+
+/// Makes the command for a program, which is looked up as set in the options.
+///
+/// With the default lookup, this is left to the platform. Otherwise the program is found here, in
+/// the allowed directories, and run from its absolute path.
+fn command(program: impl AsRef<OsStr>, options: &Options) -> Result<Command, Error> {
+	let program = program.as_ref();
+	let pinned = options
+		.command_paths
+		.iter()
+		.find(|(name, _)| OsStr::new(name) == program)
+		.map(|(_, path)| path.as_os_str());
+
+	let dirs = match &options.command_lookup {
+		CommandLookup::Path => return Ok(Command::new(pinned.unwrap_or(program))),
+		CommandLookup::TrustedPath => env::var_os("PATH")
+			.map(|path| env::split_paths(&path).filter(|dir| trusted(dir)).collect())
+			.unwrap_or_default(),
+		CommandLookup::Dirs(dirs) => {
+			if let Some(dir) = dirs.iter().find(|dir| !dir.is_absolute()) {
+				let reason = format!("{} is not an absolute directory", dir.display());
+				return Err(rejected(program, reason));
+			}
+			dirs.clone()
+		}
+	};
+
+	let path = Path::new(pinned.unwrap_or(program));
+	if path.is_absolute() {
+		check_trusted(program, path, options)?;
+		return Ok(Command::new(path));
+	} else if pinned.is_some() || path.components().count() > 1 {
+		return Err(rejected(program, "relative paths are not allowed".into()));
+	}
+
+	let not_found = || {
+		let dirs = dirs.iter().map(|dir| dir.display().to_string());
+		let reason = format!("not found in {}", dirs.collect::<Vec<_>>().join(", "));
+		rejected(program, reason)
+	};
+	if dirs.is_empty() {
+		return Err(not_found());
+	}
+
+	// a directory with a separator in it can’t be searched, so it can’t be trusted either
+	let search = env::join_paths(&dirs).map_err(|_| not_found())?;
+	let found = which::which_in(path, Some(search), "/").map_err(|_| not_found())?;
+	check_trusted(program, &found, options)?;
+	Ok(Command::new(found))
+}
+
+/// With [`CommandLookup::TrustedPath`], refuses an executable which isn’t [trusted].
+fn check_trusted(program: &OsStr, path: &Path, options: &Options) -> Result<(), Error> {
+	if options.command_lookup != CommandLookup::TrustedPath || trusted(path) {
+		return Ok(());
+	}
+
+	let reason = if path.exists() {
+		format!("{} is world-writable", path.display())
+	} else {
+		format!("{} doesn’t exist", path.display())
+	};
+	Err(rejected(program, reason))
+}
+
+/// Whether a path is absolute, and on Unix, isn’t world-writable.
+fn trusted(path: &Path) -> bool {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::PermissionsExt;
+		path.is_absolute()
+			&& path
+				.metadata()
+				.is_ok_and(|meta| meta.permissions().mode() & 0o002 == 0)
+	}
+
+	#[cfg(not(unix))]
+	{
+		path.is_absolute()
+	}
+}
+
+fn rejected(program: impl AsRef<OsStr>, reason: String) -> Error {
+	Error::CommandRejected {
+		program: program.as_ref().to_string_lossy().into_owned(),
+		reason,
+	}
+}
+
+/// Runs a command, capturing its stderr, and its stdout if asked to.
+///
+/// Returns what it printed to stdout. If it fails, the error has its arguments and stderr. If it
+/// takes longer than the timeout in the options, it’s killed; its output is then abandoned, as
+/// anything it started may be holding on to it.
+fn run(mut cmd: Command, capture: bool, options: &Options) -> Result<Vec<u8>, Error> {
+	let argv = argv(&cmd)
+		.iter()
+		.map(|arg| arg.to_string_lossy().into_owned())
+		.collect::<Vec<_>>();
+	cmd.stdin(Stdio::inherit()).stderr(Stdio::piped());
+	cmd.stdout(if capture {
+		Stdio::piped()
+	} else {
+		Stdio::inherit()
+	});
+
+	let mut child = cmd.spawn().map_err(|err| match err.kind() {
+		io::ErrorKind::NotFound => Error::CommandNotFound {
+			program: argv[0].clone(),
+		},
+		_ => err.into(),
+	})?;
+	let stdout = child.stdout.take().map(|out| thread::spawn(|| read(out)));
+	let stderr = child.stderr.take().map(|err| thread::spawn(|| read(err)));
+
+	let status = match options.command_timeout {
+		None => child.wait()?,
+		Some(timeout) => {
+			let start = Instant::now();
+			loop {
+				if let Some(status) = child.try_wait()? {
+					break status;
+				}
+
+				let elapsed = start.elapsed();
+				if elapsed >= timeout {
+					// it may have exited in the meantime
+					child.kill().ok();
+					child.wait()?;
+					return Err(Error::CommandTimeout { argv, timeout });
+				}
+				thread::sleep(POLL.min(timeout - elapsed));
+			}
+		}
+	};
+
+	let stdout = stdout.map(join).unwrap_or_default();
+	let stderr = stderr.map(join).unwrap_or_default();
+	if status.success() {
+		Ok(stdout)
+	} else {
+		Err(Error::Command {
+			argv,
+			status,
+			stderr: String::from_utf8_lossy(&stderr).into_owned(),
+		})
+	}
+}
+
+fn argv(cmd: &Command) -> Vec<OsString> {
+	[cmd.get_program()]
+		.into_iter()
+		.chain(cmd.get_args())
+		.map(OsStr::to_owned)
+		.collect()
+}
+
+fn read(mut pipe: impl Read) -> Vec<u8> {
+	let mut buf = Vec::new();
+	pipe.read_to_end(&mut buf).ok();
+	buf
+}
+
+fn join(reader: thread::JoinHandle<Vec<u8>>) -> Vec<u8> {
+	reader.join().unwrap_or_default()
+}
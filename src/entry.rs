@@ -0,0 +1,140 @@
+use std::{env, fmt, path::PathBuf};
+
+use terminfo::Database;
+
+use super::{info, Error, Options};
+
+/// A terminfo entry, as resolved for a terminal.
+///
+/// When a terminal’s own entry isn’t available, for example over SSH to a host which doesn’t know
+/// about a newer terminal, the terminfo variants fall back to the entry of a similar terminal. This
+/// records which entry was actually used, and where it was read from, so that it can be shown or
+/// logged.
+///
+/// The names tried are, in order: the terminal’s own name, known stand-ins for it, and shorter names
+/// made by dropping `-` suffixes. For example, `xterm-kitty`, then `xterm-256color`, then `xterm`.
+///
+/// ```no_run
+/// # use clearscreen::TerminfoEntry;
+/// let entry = TerminfoEntry::from_env()?;
+/// if entry.is_fallback() {
+///     eprintln!("no terminfo for {}, using {}", entry.term, entry.entry);
+/// }
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminfoEntry {
+	/// The name of the terminal the entry was looked up for.
+	pub term: String,
+
+	/// The name of the entry which was used.
+	///
+	/// This is the same as [`term`][TerminfoEntry::term] unless a fallback was used.
+	pub entry: String,
+
+	/// Where the entry was read from.
+	pub source: TerminfoSource,
+
+	/// The terminfo database.
+	pub database: Database,
+}
+
+impl TerminfoEntry {
+	/// Resolves the entry for the terminal named by the `TERM` environment variable.
+	pub fn from_env() -> Result<Self, Error> {
+		let term = env::var("TERM").map_err(|_| terminfo::Error::NotFound)?;
+		Self::resolve(&term)
+	}
+
+	/// Resolves the entry for a terminal by name.
+	///
+	/// If no entry is found, returns [`Error::TerminfoNotFound`] with the names which were tried and
+	/// the locations which were searched. If the only entries found couldn’t be read, returns
+	/// [`Error::TerminfoInvalid`] with the first of them.
+	pub fn resolve(term: &str) -> Result<Self, Error> {
+		Self::resolve_with(term, &Options::default())
+	}
+
+	/// Resolves the entry for a terminal by name, with the given [`Options`].
+	///
+	/// This is how the terminfo variants resolve entries. Only the
+	/// [`infocmp()`][Options::infocmp()] and [`command_timeout()`][Options::command_timeout()]
+	/// options are used.
+	pub fn resolve_with(term: &str, options: &Options) -> Result<Self, Error> {
+		let tried = info::candidates(term);
+		let mut invalid = Vec::new();
+
+		for entry in &tried {
+			if let Some((database, source)) = info::from_name(entry, options, &mut invalid) {
+				return Ok(Self {
+					term: term.into(),
+					entry: entry.clone(),
+					source,
+					database,
+				});
+			}
+		}
+
+		Err(match invalid.into_iter().next() {
+			Some(path) => Error::TerminfoInvalid {
+				term: term.into(),
+				path,
+			},
+			None => Error::TerminfoNotFound {
+				term: term.into(),
+				tried,
+				searched: info::search_dirs(),
+			},
+		})
+	}
+
+	/// Whether the entry is not the terminal’s own.
+	pub fn is_fallback(&self) -> bool {
+		self.term != self.entry
+	}
+}
+
+/// Where a terminfo entry was read from.
+///
+/// Its [`Display`][fmt::Display] implementation shows the path, or that the entry was embedded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerminfoSource {
+	/// A file in a terminfo directory tree, like `/usr/share/terminfo/x/xterm`.
+	File(PathBuf),
+
+	/// A hashed database, like `/usr/share/misc/terminfo.db`.
+	Hashed(PathBuf),
+
+	/// The output of `infocmp`, when enabled with [`Options::infocmp()`].
+	Infocmp,
+
+	/// The entries bundled with the `embedded-terminfo` feature.
+	Embedded,
+}
+
+impl fmt::Display for TerminfoSource {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::File(path) => write!(f, "{}", path.display()),
+			Self::Hashed(path) => write!(f, "{} (hashed database)", path.display()),
+			Self::Infocmp => write!(f, "infocmp"),
+			Self::Embedded => write!(f, "embedded entries"),
+		}
+	}
+}
+
+/// Finds where the terminfo entry for the current terminal is read from.
+///
+/// This resolves the entry as the terminfo variants do, from the `TERM` environment variable and
+/// the terminfo search path. When the entry can’t be found, the error lists the locations which
+/// were searched. To see the source of a fallback entry, or of the entry for another terminal, use
+/// [`TerminfoEntry`].
+///
+/// ```no_run
+/// # use clearscreen::terminfo_source;
+/// eprintln!("using terminfo from {}", terminfo_source()?);
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+pub fn terminfo_source() -> Result<TerminfoSource, Error> {
+	TerminfoEntry::from_env().map(|entry| entry.source)
+}
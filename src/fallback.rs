@@ -0,0 +1,189 @@
+use std::{
+	borrow::Cow,
+	fmt,
+	io::Write,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+use super::{Capabilities, ClearScreen, ClearStrategy, Error, ErrorKind, Options};
+
+/// Tries strategies in order, going on to the next when one fails in an expected way.
+///
+/// This replaces matching on the error of one [`ClearScreen`] variant to try another. By default,
+/// the next strategy is tried when the terminfo entry or a capability is missing, a command isn’t
+/// found, or actions aren’t supported by their backend; see [`on()`][Fallback::on()] to change
+/// that. Other errors are returned straight away. If every strategy fails, the last error is
+/// returned.
+///
+/// What each strategy writes is held back until it succeeds, so that one which fails partway
+/// doesn’t leave its output in front of the next one’s. This means delays in its output are waited
+/// for before any of it is written. Other effects, like changes to termios or commands printing
+/// straight to stdout with [`Options::inherit_stdout()`], can’t be held back.
+///
+/// The strategy which succeeded is recorded, and available from [`used()`][Fallback::used()].
+///
+/// ```no_run
+/// # use clearscreen::{ClearScreen, Fallback};
+/// let clear = Fallback::new(ClearScreen::Terminfo).or(ClearScreen::XtermClear);
+/// clear.clear_to_with_used(&mut std::io::stdout(), &Default::default())?;
+/// eprintln!("cleared with {}", clear.used().unwrap().name());
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+///
+/// [`Fallback::default()`] makes a chain from [`ClearScreen::default()`].
+pub struct Fallback {
+	strategies: Vec<Arc<dyn ClearStrategy>>,
+	on: Vec<ErrorKind>,
+	used: AtomicUsize,
+}
+
+const UNUSED: usize = usize::MAX;
+
+impl Fallback {
+	/// A chain starting with the given strategy.
+	pub fn new(first: impl ClearStrategy + 'static) -> Self {
+		Self::from_arc(Arc::new(first))
+	}
+
+	/// A chain starting with a shared strategy, such as one from a [`Registry`][crate::Registry].
+	pub fn from_arc(first: Arc<dyn ClearStrategy>) -> Self {
+		Self {
+			strategies: vec![first],
+			on: vec![
+				ErrorKind::TerminfoNotFound,
+				ErrorKind::CapabilityNotFound,
+				ErrorKind::CommandNotFound,
+				ErrorKind::Unsupported,
+			],
+			used: AtomicUsize::new(UNUSED),
+		}
+	}
+
+	/// Adds a strategy to try after the others.
+	pub fn or(self, next: impl ClearStrategy + 'static) -> Self {
+		self.or_arc(Arc::new(next))
+	}
+
+	/// Adds a shared strategy to try after the others.
+	pub fn or_arc(mut self, next: Arc<dyn ClearStrategy>) -> Self {
+		self.strategies.push(next);
+		self
+	}
+
+	/// Sets the kinds of errors on which the next strategy is tried.
+	pub fn on(mut self, kinds: impl IntoIterator<Item = ErrorKind>) -> Self {
+		self.on = kinds.into_iter().collect();
+		self
+	}
+
+	/// The strategies, in the order they’re tried.
+	pub fn strategies(&self) -> impl Iterator<Item = &dyn ClearStrategy> {
+		self.strategies.iter().map(|strategy| &**strategy)
+	}
+
+	/// The strategy which last succeeded, if any.
+	pub fn used(&self) -> Option<&dyn ClearStrategy> {
+		self.strategies
+			.get(self.used.load(Ordering::Relaxed))
+			.map(|strategy| &**strategy)
+	}
+
+	/// Tries the strategies with the given [`Options`], printing to a given writer, and returns
+	/// the one which succeeded.
+	pub fn clear_to_with_used(
+		&self,
+		w: &mut dyn Write,
+		options: &Options,
+	) -> Result<&dyn ClearStrategy, Error> {
+		self.used.store(UNUSED, Ordering::Relaxed);
+
+		let mut last = None;
+		for (index, strategy) in self.strategies.iter().enumerate() {
+			let mut output = Vec::new();
+			match strategy.clear_to_with(&mut output, options) {
+				Ok(()) => {
+					w.write_all(&output)?;
+					w.flush()?;
+					self.used.store(index, Ordering::Relaxed);
+					return Ok(&**strategy);
+				}
+				Err(err) if self.on.contains(&err.kind()) => last = Some(err),
+				Err(err) => return Err(err),
+			}
+		}
+
+		Err(last.expect("a fallback chain always has a strategy"))
+	}
+}
+
+impl Default for Fallback {
+	/// Makes a chain from [`ClearScreen::default()`], followed by
+	/// [`XtermClear`][ClearScreen::XtermClear] if that’s not already it.
+	///
+	/// As `XtermClear` needs neither terminfo nor commands, this still clears the screen of most
+	/// terminals where the best guess fails, for example when it needs a terminfo entry which
+	/// can’t be read.
+	fn default() -> Self {
+		let guess = ClearScreen::default();
+		let chain = Self::new(guess);
+		if guess == ClearScreen::XtermClear {
+			chain
+		} else {
+			chain.or(ClearScreen::XtermClear)
+		}
+	}
+}
+
+impl Clone for Fallback {
+	fn clone(&self) -> Self {
+		Self {
+			strategies: self.strategies.clone(),
+			on: self.on.clone(),
+			used: AtomicUsize::new(self.used.load(Ordering::Relaxed)),
+		}
+	}
+}
+
+impl fmt::Debug for Fallback {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Fallback")
+			.field("strategies", &self.strategies)
+			.field("on", &self.on)
+			.field("used", &self.used().map(|strategy| strategy.name()))
+			.finish()
+	}
+}
+
+impl ClearStrategy for Fallback {
+	/// The names of the strategies, separated by ` or `.
+	fn name(&self) -> Cow<'_, str> {
+		let names = self.strategies().map(|strategy| strategy.name());
+		names.collect::<Vec<_>>().join(" or ").into()
+	}
+
+	/// The actions of the first strategy, and whether any needs terminfo or commands.
+	fn capabilities(&self) -> Capabilities {
+		let mut strategies = self.strategies();
+		let first = strategies
+			.next()
+			.map(|s| s.capabilities())
+			.unwrap_or_default();
+		strategies.fold(first, |caps, strategy| {
+			let other = strategy.capabilities();
+			caps.terminfo(caps.terminfo || other.terminfo)
+				.command(caps.command || other.command)
+		})
+	}
+
+	/// Whether the first strategy handles the terminal.
+	fn handles_term(&self, term: &str) -> bool {
+		self.strategies[0].handles_term(term)
+	}
+
+	fn clear_to_with(&self, w: &mut dyn Write, options: &Options) -> Result<(), Error> {
+		self.clear_to_with_used(w, options).map(drop)
+	}
+}
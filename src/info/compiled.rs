@@ -0,0 +1,193 @@
+//! Reader for compiled terminfo entries, as described in ncurses’ term(5).
+//!
+//! This handles both the legacy format, with 16-bit numbers, and the format introduced in ncurses
+//! 6.1 with 32-bit numbers, as well as the extended capabilities section which may follow either.
+
+use terminfo::{names, Database, Value};
+
+/// Magic number of the legacy format, with 16-bit numbers.
+const MAGIC_LEGACY: i16 = 0o432;
+
+/// Magic number of the ncurses 6.1+ format, with 32-bit numbers.
+const MAGIC_32BIT: i16 = 0o1036;
+
+/// Parses a compiled terminfo entry.
+pub(crate) fn parse(buf: &[u8]) -> Result<Database, terminfo::Error> {
+	let mut r = Reader { buf, pos: 0 };
+
+	let number_size = match r.i16()? {
+		MAGIC_LEGACY => 2,
+		MAGIC_32BIT => 4,
+		_ => return Err(terminfo::Error::Parse),
+	};
+
+	let names_size = r.count()?;
+	let bool_count = r.count()?;
+	let num_count = r.count()?;
+	let str_count = r.count()?;
+	let table_size = r.count()?;
+
+	let names = r.bytes(names_size)?;
+	let names = names.split(|&c| c == 0).next().unwrap_or_default();
+	let names = String::from_utf8_lossy(names);
+	let mut names: Vec<&str> = names.split('|').map(str::trim).collect();
+
+	let mut db = Database::new();
+	db.name(names.remove(0));
+	if let Some(description) = names.pop() {
+		db.description(description);
+	}
+	db.aliases(names);
+
+	let bools = r.bytes(bool_count)?;
+	r.align();
+	let nums = r.numbers(num_count, number_size)?;
+	let strs = r.offsets(str_count)?;
+	let table = r.bytes(table_size)?;
+
+	for (index, _) in bools.iter().enumerate().filter(|(_, &b)| b == 1) {
+		if let Some(name) = names::BOOLEAN.get(&(index as u16)) {
+			db.raw(name, Value::True);
+		}
+	}
+
+	for (index, &n) in nums.iter().enumerate().filter(|(_, &n)| n >= 0) {
+		if let Some(name) = names::NUMBER.get(&(index as u16)) {
+			db.raw(name, Value::Number(n));
+		}
+	}
+
+	for (index, &offset) in strs.iter().enumerate() {
+		if let (Some(name), Some(value)) =
+			(names::STRING.get(&(index as u16)), string(table, offset))
+		{
+			db.raw(name, Value::String(value.into()));
+		}
+	}
+
+	// the extended section is optional, and starts on an even boundary
+	r.align();
+	if r.remaining() > 0 {
+		for (name, value) in extended(&mut r, number_size)? {
+			db.raw(name, value);
+		}
+	}
+
+	db.build().map_err(|()| terminfo::Error::Parse)
+}
+
+/// Parses the extended capabilities section, which names its own capabilities.
+fn extended(r: &mut Reader, number_size: usize) -> Result<Vec<(String, Value)>, terminfo::Error> {
+	let bool_count = r.count()?;
+	let num_count = r.count()?;
+	let str_count = r.count()?;
+	let _offset_count = r.count()?;
+	let table_size = r.count()?;
+
+	let bools = r.bytes(bool_count)?;
+	r.align();
+	let nums = r.numbers(num_count, number_size)?;
+	let strs = r.offsets(str_count)?;
+	let name_offsets = r.offsets(bool_count + num_count + str_count)?;
+	let table = r.bytes(table_size)?;
+
+	// names are stored after the string values, and their offsets are relative to that
+	let names_start = strs
+		.iter()
+		.filter_map(|&offset| Some(offset as usize + string(table, offset)?.len() + 1))
+		.max()
+		.unwrap_or(0);
+	let names_table = table.get(names_start..).ok_or(terminfo::Error::Parse)?;
+	let mut names = name_offsets.iter().map(|&offset| {
+		string(names_table, offset)
+			.map(|name| String::from_utf8_lossy(name).into_owned())
+			.ok_or(terminfo::Error::Parse)
+	});
+
+	let mut caps = Vec::new();
+
+	for &b in bools {
+		let name = names.next().ok_or(terminfo::Error::Parse)??;
+		if b == 1 {
+			caps.push((name, Value::True));
+		}
+	}
+
+	for n in nums {
+		let name = names.next().ok_or(terminfo::Error::Parse)??;
+		if n >= 0 {
+			caps.push((name, Value::Number(n)));
+		}
+	}
+
+	for offset in strs {
+		let name = names.next().ok_or(terminfo::Error::Parse)??;
+		if let Some(value) = string(table, offset) {
+			caps.push((name, Value::String(value.into())));
+		}
+	}
+
+	Ok(caps)
+}
+
+/// Reads a NUL-terminated string from a table; negative offsets mark absent or cancelled values.
+fn string(table: &[u8], offset: i16) -> Option<&[u8]> {
+	let rest = table.get(usize::try_from(offset).ok()?..)?;
+	let end = rest.iter().position(|&c| c == 0)?;
+	Some(&rest[..end])
+}
+
+struct Reader<'a> {
+	buf: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Reader<'a> {
+	fn remaining(&self) -> usize {
+		self.buf.len().saturating_sub(self.pos)
+	}
+
+	fn align(&mut self) {
+		if self.pos % 2 == 1 {
+			self.pos += 1;
+		}
+	}
+
+	fn bytes(&mut self, len: usize) -> Result<&'a [u8], terminfo::Error> {
+		let bytes = self
+			.buf
+			.get(self.pos..self.pos + len)
+			.ok_or(terminfo::Error::Parse)?;
+		self.pos += len;
+		Ok(bytes)
+	}
+
+	fn i16(&mut self) -> Result<i16, terminfo::Error> {
+		let bytes = self.bytes(2)?;
+		Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn count(&mut self) -> Result<usize, terminfo::Error> {
+		usize::try_from(self.i16()?).map_err(|_| terminfo::Error::Parse)
+	}
+
+	fn numbers(&mut self, count: usize, size: usize) -> Result<Vec<i32>, terminfo::Error> {
+		Ok(self
+			.bytes(count * size)?
+			.chunks_exact(size)
+			.map(|n| match *n {
+				[a, b] => i16::from_le_bytes([a, b]).into(),
+				[a, b, c, d] => i32::from_le_bytes([a, b, c, d]),
+				_ => unreachable!(),
+			})
+			.collect())
+	}
+
+	fn offsets(&mut self, count: usize) -> Result<Vec<i16>, terminfo::Error> {
+		Ok(self
+			.bytes(count * 2)?
+			.chunks_exact(2)
+			.map(|n| i16::from_le_bytes([n[0], n[1]]))
+			.collect())
+	}
+}
@@ -0,0 +1,50 @@
+//! Compiled terminfo entries bundled with the `embedded-terminfo` feature.
+//!
+//! These are used as a last resort, when the entry can’t be found on the system at all, as happens
+//! in minimal container images. The selection covers the most common `TERM` values among the
+//! terminals in TERMINALS.md; see `terminfo/README.md` for where they come from.
+
+macro_rules! entries {
+	($($dir:literal / $name:literal),+ $(,)?) => {
+		&[$(($name, include_bytes!(concat!("../../terminfo/", $dir, "/", $name)))),+]
+	};
+}
+
+const ENTRIES: &[(&str, &[u8])] = entries![
+	"a" / "alacritty",
+	"f" / "foot",
+	"k" / "kitty",
+	"k" / "konsole-256color",
+	"l" / "linux",
+	"p" / "putty",
+	"r" / "rxvt-unicode-256color",
+	"s" / "screen",
+	"s" / "screen-256color",
+	"s" / "st-256color",
+	"t" / "tmux",
+	"t" / "tmux-256color",
+	"v" / "vt100",
+	"v" / "vt220",
+	"w" / "wezterm",
+	"x" / "xterm",
+	"x" / "xterm-256color",
+];
+
+/// Names of bundled entries which are known by another name, and that entry.
+///
+/// ncurses has kitty’s entry as `kitty`, but kitty sets `TERM=xterm-kitty`, with the entry under
+/// that name coming from kitty itself.
+const ALIASES: &[(&str, &str)] = &[("xterm-kitty", "kitty")];
+
+/// Looks up a bundled compiled entry by terminal name.
+pub(crate) fn lookup(name: &str) -> Option<&'static [u8]> {
+	let name = ALIASES
+		.iter()
+		.find(|(alias, _)| *alias == name)
+		.map_or(name, |(_, entry)| *entry);
+
+	ENTRIES
+		.iter()
+		.find(|(entry, _)| *entry == name)
+		.map(|(_, entry)| *entry)
+}
@@ -0,0 +1,272 @@
+//! Reader for hashed terminfo databases.
+//!
+//! ncurses can be built to store its terminfo entries in a single Berkeley DB file, rather than a
+//! directory tree, and the BSDs ship their terminfo database that way as `/usr/share/misc/terminfo.db`.
+//! The file uses the hash access method of the 4.4BSD `db` library (version 1.85, with on-disk
+//! version 2), which is documented only by its source: see `hash.h` and `hash_page.c` there.
+//!
+//! Rather than reimplementing the hash function, lookups scan every bucket. Databases are small, and
+//! this is only done once per load.
+//!
+//! Records are keyed by terminal name. The value of each is a one-byte tag followed by either a
+//! compiled entry (tag 0), or the full name field of the entry the key is an alias of (tag 2).
+
+const MAGIC: u32 = 0x061561;
+const VERSION: u32 = 2;
+
+/// Header: 17 words, 32 spares, then 32 half-word bitmap addresses.
+const HEADER_SIZE: usize = 4 * (17 + 32) + 2 * 32;
+
+// Markers in the data offset slot of a pair, below which the slot doesn’t hold an offset.
+const REAL_KEY: u16 = 4;
+const OVFLPAGE: u16 = 0;
+const PARTIAL_KEY: u16 = 1;
+const FULL_KEY: u16 = 2;
+const FULL_KEY_DATA: u16 = 3;
+
+const TAG_ENTRY: u8 = 0;
+const TAG_ALIAS: u8 = 2;
+
+/// Looks up a compiled terminfo entry by terminal name, following aliases.
+pub(crate) fn lookup(file: &[u8], name: &str) -> Option<Vec<u8>> {
+	let db = Hash::new(file)?;
+	let mut key = name.as_bytes().to_vec();
+
+	// aliases point to the full name field, which is itself a key; never more than one level deep
+	for _ in 0..2 {
+		let value = db.get(&key)?;
+		match value.split_first()? {
+			(&TAG_ENTRY, entry) => return Some(entry.to_vec()),
+			(&TAG_ALIAS, names) => key = names.to_vec(),
+			_ => return None,
+		}
+	}
+
+	None
+}
+
+struct Hash<'a> {
+	file: &'a [u8],
+	little_endian: bool,
+	bsize: usize,
+	max_bucket: u32,
+	hdrpages: u32,
+	spares: [u32; 32],
+}
+
+impl<'a> Hash<'a> {
+	fn new(file: &'a [u8]) -> Option<Self> {
+		let header = file.get(..HEADER_SIZE)?;
+
+		// the header is written in network order, but accept the other way around too in case the
+		// database was written by a library which doesn’t follow that
+		let little_endian_header = header[..4] == MAGIC.to_le_bytes();
+		let word = |i: usize| {
+			let word = header[i * 4..i * 4 + 4].try_into().unwrap();
+			if little_endian_header {
+				u32::from_le_bytes(word)
+			} else {
+				u32::from_be_bytes(word)
+			}
+		};
+
+		if word(0) != MAGIC || word(1) != VERSION {
+			return None;
+		}
+
+		let little_endian = match word(2) {
+			1234 => true,
+			4321 => false,
+			_ => return None,
+		};
+
+		let bsize = word(3) as usize;
+		if !(64..=65536).contains(&bsize) {
+			return None;
+		}
+
+		let mut spares = [0; 32];
+		for (i, spare) in spares.iter_mut().enumerate() {
+			*spare = word(17 + i);
+		}
+
+		Some(Self {
+			file,
+			little_endian,
+			bsize,
+			max_bucket: word(10),
+			hdrpages: word(15),
+			spares,
+		})
+	}
+
+	/// Finds the value for a key, scanning all buckets.
+	fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+		for bucket in 0..=self.max_bucket {
+			let mut found = None;
+			self.scan_bucket(bucket, &mut |k, v| {
+				if k == key {
+					found = Some(v);
+				}
+				found.is_none()
+			})?;
+
+			if found.is_some() {
+				return found;
+			}
+		}
+
+		None
+	}
+
+	/// Calls `f` with every pair in a bucket and its overflow pages, until it returns false.
+	fn scan_bucket(&self, bucket: u32, f: &mut dyn FnMut(&[u8], Vec<u8>) -> bool) -> Option<()> {
+		let mut page_no = self.bucket_page(bucket)?;
+
+		// bound the walk by the number of pages in the file, in case the chain loops
+		for _ in 0..self.file.len() / self.bsize + 1 {
+			let page = self.page(page_no)?;
+			let n = page.get(0)? as usize;
+			if n == 0 {
+				return Some(());
+			}
+
+			// a page holding only a link to the next has OVFLPAGE there instead
+			if (PARTIAL_KEY..=FULL_KEY_DATA).contains(&page.get(2)?) {
+				let (key, data, last) = self.big_pair(page_no)?;
+				if !f(&key, data) {
+					return Some(());
+				}
+
+				let last = self.page(last)?;
+				if last.get(0)? < 4 {
+					return Some(());
+				}
+				page_no = self.overflow_page(last.get(3)?)?;
+				continue;
+			}
+
+			let mut end = self.bsize;
+			let mut next = None;
+			for i in (1..n).step_by(2) {
+				let key_off = page.get(i)?;
+				let data_off = page.get(i + 1)?;
+				if data_off == OVFLPAGE {
+					next = Some(key_off);
+					break;
+				}
+				if data_off < REAL_KEY {
+					return None;
+				}
+
+				let key = page.bytes(key_off as usize, end)?;
+				let data = page.bytes(data_off as usize, key_off as usize)?;
+				if !f(key, data.to_vec()) {
+					return Some(());
+				}
+				end = data_off as usize;
+			}
+
+			match next {
+				Some(addr) => page_no = self.overflow_page(addr)?,
+				None => return Some(()),
+			}
+		}
+
+		None
+	}
+
+	/// Reads a pair too big to fit on a page, which spans a chain of overflow pages.
+	///
+	/// Returns the key, the data, and the number of the page the pair ends on.
+	fn big_pair(&self, mut page_no: u32) -> Option<(Vec<u8>, Vec<u8>, u32)> {
+		let mut key = Vec::new();
+		let mut data = Vec::new();
+		let pages = self.file.len() / self.bsize + 1;
+
+		// key pages: each holds a chunk of key at the end of the page
+		let mut in_key = true;
+		for _ in 0..pages {
+			let page = self.page(page_no)?;
+			let n = page.get(0)? as usize;
+			let off = page.get(1)? as usize;
+
+			if in_key {
+				key.extend_from_slice(page.bytes(off, self.bsize)?);
+				match page.get(2)? {
+					PARTIAL_KEY => {}
+					FULL_KEY => in_key = false,
+					FULL_KEY_DATA => {
+						// the key ends here, and the data starts on the same page, below the key
+						data.extend_from_slice(page.bytes(page.get(n)? as usize, off)?);
+						if page.get(n + 1)? != 0 {
+							return Some((key, data, page_no));
+						}
+						in_key = false;
+					}
+					_ => return None,
+				}
+			} else {
+				data.extend_from_slice(page.bytes(off, self.bsize)?);
+				match page.get(2)? {
+					FULL_KEY => {}
+					FULL_KEY_DATA => return Some((key, data, page_no)),
+					_ => return None,
+				}
+			}
+
+			page_no = self.overflow_page(page.get(n.checked_sub(1)?)?)?;
+		}
+
+		None
+	}
+
+	fn page(&self, page_no: u32) -> Option<Page<'_>> {
+		let start = page_no as usize * self.bsize;
+		Some(Page {
+			bytes: self.file.get(start..start + self.bsize)?,
+			little_endian: self.little_endian,
+		})
+	}
+
+	fn bucket_page(&self, bucket: u32) -> Option<u32> {
+		let spares = if bucket == 0 {
+			0
+		} else {
+			// log2 rounded up, as the library computes it
+			let log2 = (bucket + 1).next_power_of_two().trailing_zeros();
+			*self.spares.get(log2.checked_sub(1)? as usize)?
+		};
+
+		bucket.checked_add(self.hdrpages)?.checked_add(spares)
+	}
+
+	/// Converts an overflow address (a split point and a page offset) to a page number.
+	fn overflow_page(&self, addr: u16) -> Option<u32> {
+		let split = u32::from(addr >> 11);
+		let bucket = 1u32.checked_shl(split)? - 1;
+		self.bucket_page(bucket)?
+			.checked_add(u32::from(addr & 0x7ff))
+	}
+}
+
+/// A page, addressed as an array of half-words in the database’s byte order.
+struct Page<'a> {
+	bytes: &'a [u8],
+	little_endian: bool,
+}
+
+impl<'a> Page<'a> {
+	fn get(&self, index: usize) -> Option<u16> {
+		let half: [u8; 2] = self.bytes.get(index * 2..index * 2 + 2)?.try_into().ok()?;
+		Some(if self.little_endian {
+			u16::from_le_bytes(half)
+		} else {
+			u16::from_be_bytes(half)
+		})
+	}
+
+	fn bytes(&self, start: usize, end: usize) -> Option<&'a [u8]> {
+		self.bytes.get(start..end)
+	}
+}
@@ -0,0 +1,520 @@
+use std::{
+	borrow::Cow,
+	env, fs, io,
+	path::{Path, PathBuf},
+};
+
+use terminfo::{
+	expand::{Context, Parameter},
+	Database, Expand, Value,
+};
+
+use super::{command, unix, Error, Options, Overlay, TerminfoEntry, TerminfoSource};
+
+mod compiled;
+#[cfg(feature = "embedded-terminfo")]
+mod embedded;
+mod hashed;
+mod padding;
+pub(crate) mod source;
+
+pub(crate) use padding::{Delays, Output, Sleeping};
+
+/// A terminfo entry, with the capabilities of an [`Overlay`] on top.
+pub(crate) struct Info<'a> {
+	database: Cow<'a, Database>,
+	term: Cow<'a, str>,
+	overlay: Option<&'a Overlay>,
+
+	/// The entry name and where it was found, if it was looked up.
+	found: Option<(String, TerminfoSource)>,
+}
+
+impl<'a> Info<'a> {
+	/// An entry without an overlay.
+	pub(crate) fn new(database: &'a Database) -> Self {
+		Self {
+			database: Cow::Borrowed(database),
+			term: Cow::Borrowed(database.name()),
+			overlay: None,
+			found: None,
+		}
+	}
+
+	/// The error for a missing capability, with where the entry came from if it was looked up.
+	pub(crate) fn missing(&self, cap: &'static str) -> Error {
+		let (entry, location) = match &self.found {
+			Some((entry, source)) => (Some(entry.clone()), Some(source.clone())),
+			None => (None, None),
+		};
+		Error::TerminfoCapNotFound {
+			cap,
+			term: self.term.to_string(),
+			entry,
+			location,
+		}
+	}
+
+	/// Looks up a capability by its short or long name, in the overlay and then in the database.
+	pub(crate) fn raw(&self, name: &str) -> Option<&Value> {
+		match self
+			.overlay
+			.and_then(|overlay| overlay.get(&self.term, name))
+		{
+			Some(value) => value,
+			None => self.database.raw(name),
+		}
+	}
+}
+
+/// Loads the terminfo database selected in the options, or else from the environment.
+///
+/// The overlay in the options applies to the terminal’s name: the one given in the options if
+/// any, otherwise the name of the given database, or else `TERM`.
+pub(crate) fn load(options: &Options) -> Result<Info<'_>, Error> {
+	let overlay = Some(&options.overlay).filter(|overlay| !overlay.is_empty());
+
+	if let Some(info) = &options.database {
+		return Ok(Info {
+			database: Cow::Borrowed(info),
+			term: options
+				.term
+				.as_deref()
+				.map_or(Cow::Borrowed(info.name()), Cow::Borrowed),
+			overlay,
+			found: None,
+		});
+	}
+
+	let term = match &options.term {
+		Some(term) => term.clone(),
+		None => env::var("TERM").map_err(|_| terminfo::Error::NotFound)?,
+	};
+	let entry = TerminfoEntry::resolve_with(&term, options)?;
+	Ok(Info {
+		database: Cow::Owned(entry.database),
+		term: Cow::Owned(entry.term),
+		overlay,
+		found: Some((entry.entry, entry.source)),
+	})
+}
+
+/// Known stand-ins for terminals whose own entries are often missing.
+///
+/// Each is the closest widely available entry, per the research in TERMINALS.md.
+const FALLBACKS: &[(&str, &str)] = &[
+	("alacritty", "xterm-256color"),
+	("foot", "xterm-256color"),
+	("foot-extra", "xterm-256color"),
+	("ghostty", "xterm-256color"),
+	("gnome-256color", "xterm-256color"),
+	("iTerm2.app", "xterm-256color"),
+	("kitty", "xterm-256color"),
+	("konsole", "xterm"),
+	("konsole-256color", "xterm-256color"),
+	("ms-terminal", "xterm-256color"),
+	("rxvt-unicode", "rxvt"),
+	("rxvt-unicode-256color", "rxvt-unicode"),
+	("st", "xterm"),
+	("st-256color", "xterm-256color"),
+	("tmux", "screen"),
+	("tmux-256color", "screen-256color"),
+	("vte-256color", "xterm-256color"),
+	("wezterm", "xterm-256color"),
+	("xterm-ghostty", "xterm-256color"),
+	("xterm-kitty", "xterm-256color"),
+];
+
+/// The names of the entries to try for a terminal, in order.
+///
+/// For each name, starting with the terminal’s own: its known stand-in, and the name without its
+/// last `-` suffix; and so on, skipping duplicates.
+pub(crate) fn candidates(term: &str) -> Vec<String> {
+	let mut names = vec![term.to_string()];
+	let mut i = 0;
+	while let Some(name) = names.get(i).cloned() {
+		let fallback = FALLBACKS
+			.iter()
+			.find(|(from, _)| *from == name)
+			.map(|(_, to)| *to);
+		let base = name.rsplit_once('-').map(|(base, _)| base);
+
+		for next in fallback.into_iter().chain(base) {
+			if !next.is_empty() && !names.iter().any(|n| n == next) {
+				names.push(next.into());
+			}
+		}
+		i += 1;
+	}
+	names
+}
+
+/// Loads the terminfo entry for a terminal by name, and where it was found.
+///
+/// The directories of the search path are tried in turn, looking for the entry both in the usual
+/// directory tree and in a hashed database. Entries are read with the terminfo crate, or with our
+/// own parser when it can’t. If that fails and it’s enabled in the options, `infocmp` is asked
+/// for the entry. Finally, with the `embedded-terminfo` feature, the bundled entries are used.
+///
+/// Files which are found but can’t be read are added to `invalid`, and the search goes on.
+pub(crate) fn from_name(
+	name: &str,
+	options: &Options,
+	invalid: &mut Vec<PathBuf>,
+) -> Option<(Database, TerminfoSource)> {
+	search(name, invalid)
+		.or_else(|| {
+			let info = options.infocmp.then(|| infocmp(name, options))??;
+			Some((info, TerminfoSource::Infocmp))
+		})
+		.or_else(|| Some((embedded(name)?, TerminfoSource::Embedded)))
+}
+
+/// Reads an entry from the output of `infocmp`, which can read any database that ncurses can.
+fn infocmp(name: &str, options: &Options) -> Option<Database> {
+	if name.is_empty() || name.starts_with('-') {
+		return None;
+	}
+
+	source::parse(&command::infocmp(name, options).ok()?)
+}
+
+#[cfg(feature = "embedded-terminfo")]
+fn embedded(name: &str) -> Option<Database> {
+	compiled::parse(embedded::lookup(name)?).ok()
+}
+
+#[cfg(not(feature = "embedded-terminfo"))]
+fn embedded(_name: &str) -> Option<Database> {
+	None
+}
+
+fn search(name: &str, invalid: &mut Vec<PathBuf>) -> Option<(Database, TerminfoSource)> {
+	let first = name.chars().next()?;
+	if name.contains('/') {
+		return None;
+	}
+
+	for dir in search_dirs() {
+		if dir.is_dir() {
+			for sub in [first.to_string(), format!("{:x}", first as u32)] {
+				let path = dir.join(sub).join(name);
+				match fs::read(&path) {
+					Ok(entry) => match parse(&entry) {
+						Some(info) => return Some((info, TerminfoSource::File(path))),
+						None => invalid.push(path),
+					},
+					Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+					Err(_) => invalid.push(path),
+				}
+			}
+		}
+
+		if let Some(path) = hashed_path(&dir) {
+			let Ok(file) = fs::read(&path) else {
+				invalid.push(path);
+				continue;
+			};
+
+			if let Some(entry) = hashed::lookup(&file, name) {
+				match parse(&entry) {
+					Some(info) => return Some((info, TerminfoSource::Hashed(path))),
+					None => invalid.push(path),
+				}
+			}
+		}
+	}
+
+	None
+}
+
+fn parse(entry: &[u8]) -> Option<Database> {
+	Database::from_buffer(entry)
+		.or_else(|_| compiled::parse(entry))
+		.ok()
+}
+
+/// The terminfo search path, as described in ncurses’ terminfo(5).
+pub(crate) fn search_dirs() -> Vec<PathBuf> {
+	let mut dirs = Vec::new();
+
+	if let Some(dir) = env::var_os("TERMINFO") {
+		dirs.push(dir.into());
+	}
+
+	if let Some(home) = env::var_os("HOME") {
+		dirs.push(Path::new(&home).join(".terminfo"));
+	}
+
+	if let Ok(list) = env::var("TERMINFO_DIRS") {
+		dirs.extend(
+			list.split(':')
+				.filter(|dir| !dir.is_empty())
+				.map(PathBuf::from),
+		);
+	}
+
+	// non-FHS systems like Termux
+	if let Some(prefix) = env::var_os("PREFIX") {
+		let prefix = Path::new(&prefix);
+		for dir in ["etc/terminfo", "lib/terminfo", "share/terminfo"] {
+			dirs.push(prefix.join(dir));
+		}
+	}
+
+	for dir in [
+		"/etc/terminfo",
+		"/lib/terminfo",
+		"/usr/share/terminfo",
+		"/usr/local/share/terminfo",
+		"/usr/local/share/site-terminfo",
+		"/boot/system/data/terminfo",
+		"/usr/share/misc/terminfo",
+	] {
+		dirs.push(dir.into());
+	}
+
+	dirs
+}
+
+/// Where the hashed database for a search path entry would be: either the entry itself if it names
+/// a `.db` file, or a file alongside it with `.db` appended.
+fn hashed_path(dir: &Path) -> Option<PathBuf> {
+	if dir.extension().is_some_and(|ext| ext == "db") && dir.is_file() {
+		return Some(dir.into());
+	}
+
+	let mut path = dir.as_os_str().to_owned();
+	path.push(".db");
+	let path = PathBuf::from(path);
+	path.is_file().then_some(path)
+}
+
+/// Looks up a string capability by its short or long name, including user-defined capabilities.
+pub(crate) fn string_cap<'a>(info: &'a Info, name: &str) -> Option<&'a [u8]> {
+	match info.raw(name) {
+		Some(Value::String(value)) => Some(value),
+		_ => None,
+	}
+}
+
+/// Looks up a numeric capability by its short or long name.
+pub(crate) fn number_cap(info: &Info, name: &str) -> Option<i32> {
+	match info.raw(name) {
+		Some(Value::Number(value)) => Some(*value),
+		_ => None,
+	}
+}
+
+/// Expands a string capability with no parameters, and writes it out.
+pub(crate) fn write_cap(
+	info: &Info,
+	name: &str,
+	ctx: &mut Context,
+	delays: &Delays,
+	w: &mut impl Output,
+) -> Result<bool, Error> {
+	write_cap_with(info, name, &[], ctx, delays, w)
+}
+
+/// Expands a string capability with parameters, and writes it out, applying its delays.
+pub(crate) fn write_cap_with(
+	info: &Info,
+	name: &str,
+	params: &[Parameter],
+	ctx: &mut Context,
+	delays: &Delays,
+	w: &mut impl Output,
+) -> Result<bool, Error> {
+	if let Some(seq) = string_cap(info, name) {
+		let mut expanded = Vec::new();
+		seq.expand(&mut expanded, params, ctx)?;
+		delays.write(&expanded, w)?;
+		Ok(true)
+	} else {
+		Ok(false)
+	}
+}
+
+/// Capabilities used by [`TerminfoRecover`][crate::ClearScreen::TerminfoRecover], in order.
+const RECOVER: &[&str] = &[
+	"rmcup", "cnorm", "rmkx", "smam", "rmso", "rmul", "ritm", "rmir", "sgr0",
+];
+
+/// Writes those of the [`RECOVER`] capabilities which the entry has, and returns their names.
+///
+/// The capabilities in `skip` are taken to have been written already, and aren’t written again.
+pub(crate) fn recover(
+	info: &Info,
+	options: &Options,
+	skip: &[&str],
+	w: &mut impl Output,
+) -> Result<Vec<&'static str>, Error> {
+	let mut ctx = Context::default();
+	let delays = Delays::new(info, options, 1);
+	let mut applied = Vec::new();
+	for name in RECOVER.iter().filter(|name| !skip.contains(name)) {
+		if write_cap(info, name, &mut ctx, &delays, w)? {
+			applied.push(*name);
+		}
+	}
+
+	w.flush()?;
+	if applied.is_empty() && skip.is_empty() {
+		Err(info.missing("recover"))
+	} else {
+		Ok(applied)
+	}
+}
+
+/// Writes the reset sequence as ncurses’ `tput reset` does.
+///
+/// This follows `send_init_strings()` in ncurses’ `reset_cmd.c`: for each step, the reset string is
+/// used if present, otherwise the init string. The `iprog` capability is not run. As there, no
+/// lines are considered affected, so proportional delays are skipped.
+pub(crate) fn reset(info: &Info, options: &Options, w: &mut impl Output) -> Result<(), Error> {
+	let mut ctx = Context::default();
+	let delays = Delays::new(info, options, 0);
+	let mut reset = false;
+
+	reset |= write_cap(info, or_init(info, "rs1", "is1"), &mut ctx, &delays, w)?;
+	reset |= write_cap(info, or_init(info, "rs2", "is2"), &mut ctx, &delays, w)?;
+
+	let columns = columns(info);
+	reset |= reset_margins(info, columns, &mut ctx, &delays, w)?;
+	reset |= reset_tabs(info, columns, &mut ctx, &delays, w)?;
+
+	if let Some(path) = string_cap(info, or_init(info, "rf", "if")) {
+		// rf and if are paths to files whose contents are to be output
+		let path = String::from_utf8_lossy(path);
+		w.write_all(&fs::read(path.as_ref())?)?;
+		reset = true;
+	}
+
+	reset |= write_cap(info, or_init(info, "rs3", "is3"), &mut ctx, &delays, w)?;
+
+	w.flush()?;
+	if reset {
+		Ok(())
+	} else {
+		Err(info.missing("reset"))
+	}
+}
+
+fn or_init<'a>(info: &Info, reset: &'a str, init: &'a str) -> &'a str {
+	if string_cap(info, reset).is_some() {
+		reset
+	} else {
+		init
+	}
+}
+
+fn reset_margins(
+	info: &Info,
+	columns: i32,
+	ctx: &mut Context,
+	delays: &Delays,
+	w: &mut impl Output,
+) -> Result<bool, Error> {
+	let right = Parameter::from(columns - 1);
+
+	if write_cap(info, "mgc", ctx, delays, w)? {
+		return Ok(true);
+	}
+
+	if string_cap(info, "smglr").is_some() {
+		return write_cap_with(info, "smglr", &[0.into(), right], ctx, delays, w);
+	}
+
+	if string_cap(info, "smglp").is_some() && string_cap(info, "smgrp").is_some() {
+		write_cap_with(info, "smglp", &[0.into()], ctx, delays, w)?;
+		return write_cap_with(info, "smgrp", &[right], ctx, delays, w);
+	}
+
+	if string_cap(info, "smgl").is_some() && string_cap(info, "smgr").is_some() {
+		carriage_return(info, ctx, delays, w)?;
+		write_cap(info, "smgl", ctx, delays, w)?;
+		if !write_cap_with(info, "cuf", &[right], ctx, delays, w)? {
+			for _ in 1..columns {
+				w.write_all(b" ")?;
+			}
+		}
+		write_cap(info, "smgr", ctx, delays, w)?;
+		carriage_return(info, ctx, delays, w)?;
+		return Ok(true);
+	}
+
+	Ok(false)
+}
+
+// As ncurses’ `reset_tabstops()`: unless the initial tab stops are at the usual 8 columns, they’re
+// cleared, then set every `it` columns if that’s more than 1.
+fn reset_tabs(
+	info: &Info,
+	columns: i32,
+	ctx: &mut Context,
+	delays: &Delays,
+	w: &mut impl Output,
+) -> Result<bool, Error> {
+	let Some(interval) = number_cap(info, "it").filter(|&it| it >= 0 && it != 8) else {
+		return Ok(false);
+	};
+
+	if string_cap(info, "hts").is_none() || string_cap(info, "tbc").is_none() {
+		return Ok(false);
+	}
+
+	carriage_return(info, ctx, delays, w)?;
+	write_cap(info, "tbc", ctx, delays, w)?;
+	if interval > 1 {
+		let interval = interval.min(columns);
+		for _ in (interval..columns).step_by(interval as usize) {
+			w.write_all(&b" ".repeat(interval as usize))?;
+			write_cap(info, "hts", ctx, delays, w)?;
+		}
+		carriage_return(info, ctx, delays, w)?;
+	}
+
+	Ok(true)
+}
+
+/// Writes the `cr` capability, or a plain carriage return if the entry has none, as ncurses does.
+fn carriage_return(
+	info: &Info,
+	ctx: &mut Context,
+	delays: &Delays,
+	w: &mut impl Output,
+) -> Result<(), Error> {
+	if !write_cap(info, "cr", ctx, delays, w)? {
+		w.write_all(b"\r")?;
+	}
+	Ok(())
+}
+
+/// The terminal height, as ncurses determines it.
+///
+/// The `LINES` environment variable has priority, then the size of the terminal, and finally
+/// the `lines` capability. Defaults to 24.
+pub(crate) fn lines(info: &Info) -> i32 {
+	env::var("LINES")
+		.ok()
+		.and_then(|l| l.parse().ok())
+		.filter(|&l: &i32| l > 0)
+		.or_else(|| unix::lines().map(i32::from))
+		.or_else(|| number_cap(info, "lines").filter(|&l| l > 0))
+		.unwrap_or(24)
+}
+
+/// The terminal width, as ncurses determines it.
+///
+/// The `COLUMNS` environment variable has priority, then the size of the terminal, and finally
+/// the `cols` capability. Defaults to 80.
+pub(crate) fn columns(info: &Info) -> i32 {
+	env::var("COLUMNS")
+		.ok()
+		.and_then(|c| c.parse().ok())
+		.filter(|&c: &i32| c > 0)
+		.or_else(|| unix::columns().map(i32::from))
+		.or_else(|| number_cap(info, "cols").filter(|&c| c > 0))
+		.unwrap_or(80)
+}
@@ -0,0 +1,179 @@
+//! Delays in capabilities, as in `$<50>`.
+//!
+//! This follows `tputs()` in ncurses’ `lib_tputs.c`: the delay is a number of milliseconds, with
+//! up to one decimal place, optionally followed by `*` (proportional to the number of lines
+//! affected) and `/` (mandatory). Delays which aren’t mandatory only apply
+//! when the terminal doesn’t use XON/XOFF flow control, and the baud rate is at least the padding
+//! baud rate (`pb`). A delay is then done by writing enough pad characters (`pad`, or NUL) to take
+//! that long to transmit, or if the terminal has `npc`, by flushing and sleeping.
+
+use std::{
+	io::{self, Write},
+	thread::sleep,
+	time::Duration,
+};
+
+use super::{number_cap, string_cap, unix, Error, Info, Options};
+use crate::Padding;
+
+/// Where capabilities are written, with a way to wait for delays which can’t be padded.
+pub(crate) trait Output: Write {
+	/// Waits, once what was written so far has been sent.
+	fn pause(&mut self, duration: Duration) -> io::Result<()>;
+}
+
+/// Output to a writer, which sleeps for delays.
+pub(crate) struct Sleeping<W>(pub(crate) W);
+
+impl<W: Write> Write for Sleeping<W> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.0.flush()
+	}
+}
+
+impl<W: Write> Output for Sleeping<W> {
+	fn pause(&mut self, duration: Duration) -> io::Result<()> {
+		self.0.flush()?;
+		sleep(duration);
+		Ok(())
+	}
+}
+
+/// Bits transmitted per character, as ncurses counts them.
+const BAUDBYTE: u64 = 9;
+
+pub(crate) struct Delays {
+	padding: Padding,
+	lines: u64,
+	normal: bool,
+	baud_rate: u32,
+	pad_char: Option<u8>,
+}
+
+impl Delays {
+	/// Prepares to apply delays for an action which affects the given number of lines.
+	pub(crate) fn new(info: &Info, options: &Options, lines: i32) -> Self {
+		let baud_rate = options
+			.baud_rate
+			.or_else(unix::baud_rate)
+			.unwrap_or_default();
+		let normal = !flag(info, "xon")
+			&& number_cap(info, "pb")
+				.is_some_and(|pb| pb > 0 && i64::from(baud_rate) >= i64::from(pb));
+		let pad_char = if flag(info, "npc") {
+			None
+		} else {
+			Some(
+				string_cap(info, "pad")
+					.and_then(|pad| pad.first().copied())
+					.unwrap_or(0),
+			)
+		};
+
+		Self {
+			padding: options.padding,
+			lines: u64::try_from(lines).unwrap_or_default(),
+			normal,
+			baud_rate,
+			pad_char,
+		}
+	}
+
+	/// Writes an expanded capability, applying the delays it calls for, and removing the rest.
+	pub(crate) fn write(&self, mut seq: &[u8], w: &mut impl Output) -> Result<(), Error> {
+		while let Some(start) = seq.windows(2).position(|s| s == b"$<") {
+			w.write_all(&seq[..start])?;
+			let spec = &seq[start + 2..];
+
+			// not a delay: output as-is
+			if !spec
+				.first()
+				.is_some_and(|&c| c.is_ascii_digit() || c == b'.')
+				|| !spec.contains(&b'>')
+			{
+				w.write_all(b"$<")?;
+				seq = spec;
+				continue;
+			}
+
+			let (tenths, proportional, mandatory, len) = parse(spec);
+			let tenths = if proportional {
+				tenths.saturating_mul(self.lines)
+			} else {
+				tenths
+			};
+
+			let apply = match self.padding {
+				Padding::Auto => mandatory || self.normal,
+				Padding::Always => true,
+				Padding::Off => false,
+			};
+			if apply && tenths > 0 {
+				self.delay(tenths / 10, w)?;
+			}
+
+			// skip the closing >
+			seq = spec.get(len + 1..).unwrap_or_default();
+		}
+
+		w.write_all(seq)?;
+		Ok(())
+	}
+
+	fn delay(&self, ms: u64, w: &mut impl Output) -> Result<(), Error> {
+		if let Some(pad) = self.pad_char {
+			let count = ms * u64::from(self.baud_rate) / (BAUDBYTE * 1000);
+			for _ in 0..count {
+				w.write_all(&[pad])?;
+			}
+		} else {
+			w.pause(Duration::from_millis(ms))?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Parses the inside of a delay, returning tenths of milliseconds, whether it is proportional and
+/// mandatory, and the length that was parsed.
+fn parse(spec: &[u8]) -> (u64, bool, bool, usize) {
+	let mut i = 0;
+	let mut tenths: u64 = 0;
+
+	while let Some(d) = spec.get(i).filter(|c| c.is_ascii_digit()) {
+		tenths = tenths
+			.saturating_mul(10)
+			.saturating_add(u64::from(d - b'0'));
+		i += 1;
+	}
+	tenths = tenths.saturating_mul(10);
+
+	if spec.get(i) == Some(&b'.') {
+		i += 1;
+		if let Some(d) = spec.get(i).filter(|c| c.is_ascii_digit()) {
+			tenths += u64::from(d - b'0');
+			i += 1;
+		}
+		while spec.get(i).is_some_and(u8::is_ascii_digit) {
+			i += 1;
+		}
+	}
+
+	let mut proportional = false;
+	let mut mandatory = false;
+	while let Some(&c) = spec.get(i).filter(|&&c| c == b'*' || c == b'/') {
+		proportional |= c == b'*';
+		mandatory |= c == b'/';
+		i += 1;
+	}
+
+	(tenths, proportional, mandatory, i)
+}
+
+fn flag(info: &Info, name: &str) -> bool {
+	matches!(info.raw(name), Some(terminfo::Value::True))
+}
@@ -0,0 +1,154 @@
+//! Reader for terminfo source, the text format which `tic` compiles and `infocmp` prints.
+//!
+//! An entry starts with its names, separated by `|`, the last being a description. Capabilities
+//! follow, separated by commas: `name=string`, `name#number`, a bare `name` for booleans, or
+//! `name@` for a capability which is cancelled. Lines starting with `#` are comments.
+//!
+//! Only single entries are handled, without `use=` references to others, as `infocmp` prints them.
+//! See terminfo(5) for the details, notably the escapes in strings.
+
+use terminfo::{Database, Value};
+
+/// Parses a single terminfo entry in source form.
+pub(crate) fn parse(text: &str) -> Option<Database> {
+	let mut fields = Vec::new();
+	for line in text.lines().map(str::trim_start) {
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+
+		// a line normally ends with a comma, leaving an empty field after it
+		fields.extend(split(line));
+	}
+
+	let mut fields = fields.into_iter();
+	let names = fields.next()?;
+	let mut names = names.split('|').collect::<Vec<_>>();
+	let description = if names.len() > 1 { names.pop() } else { None };
+	let (name, aliases) = names.split_first()?;
+
+	let mut builder = Database::new();
+	builder.name(*name).aliases(aliases.iter().copied());
+	if let Some(description) = description {
+		builder.description(description);
+	}
+
+	for field in fields {
+		// spaces before a comma are part of the value, so only the start is trimmed
+		let field = field.trim_start();
+		if field.is_empty() {
+			continue;
+		}
+
+		// as tic does, a malformed capability is skipped rather than failing the whole entry
+		if let Ok((name, Some(value))) = capability(field) {
+			builder.raw(name, value);
+		}
+	}
+
+	builder.build().ok()
+}
+
+/// Splits capabilities on commas, except where escaped by `\` or `^`.
+pub(crate) fn split(s: &str) -> Vec<&str> {
+	let mut fields = Vec::new();
+	let mut start = 0;
+	let mut prev = None;
+	let mut chars = s.char_indices();
+	while let Some((i, c)) = chars.next() {
+		match c {
+			'\\' => {
+				chars.next();
+			}
+			// as in tic, `%^` is the XOR operator, not a control character
+			'^' if prev != Some('%') => {
+				chars.next();
+			}
+			',' => {
+				fields.push(&s[start..i]);
+				start = i + 1;
+			}
+			_ => {}
+		}
+		prev = Some(c);
+	}
+	fields.push(&s[start..]);
+	fields
+}
+
+/// Parses a capability into its name and value, which is `None` if it’s cancelled.
+pub(crate) fn capability(cap: &str) -> Result<(&str, Option<Value>), &'static str> {
+	let (name, value) = if let Some((name, value)) = cap.split_once('=') {
+		(name, Some(Value::String(unescape(value)?)))
+	} else if let Some((name, value)) = cap.split_once('#') {
+		let value = number(value).ok_or("invalid number")?;
+		(name, Some(Value::Number(value)))
+	} else if let Some(name) = cap.strip_suffix('@') {
+		(name, None)
+	} else {
+		(cap, Some(Value::True))
+	};
+
+	if name.is_empty() {
+		Err("missing capability name")
+	} else {
+		Ok((name, value))
+	}
+}
+
+/// Numbers are decimal, or octal with a leading 0, or hexadecimal with a leading 0x.
+fn number(s: &str) -> Option<i32> {
+	if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+		i32::from_str_radix(hex, 16).ok()
+	} else if s.len() > 1 && s.starts_with('0') {
+		i32::from_str_radix(&s[1..], 8).ok()
+	} else {
+		s.parse().ok()
+	}
+}
+
+/// Interprets the escapes of string capabilities.
+fn unescape(s: &str) -> Result<Vec<u8>, &'static str> {
+	let mut out = Vec::new();
+	let mut prev = None;
+	let mut bytes = s.bytes();
+	while let Some(b) = bytes.next() {
+		match b {
+			b'\\' => {
+				let c = bytes.next().ok_or("unterminated escape")?;
+				match c {
+					b'E' | b'e' => out.push(0x1b),
+					b'n' | b'l' => out.push(b'\n'),
+					b'r' => out.push(b'\r'),
+					b't' => out.push(b'\t'),
+					b'b' => out.push(0x08),
+					b'f' => out.push(0x0c),
+					b's' => out.push(b' '),
+					b'a' => out.push(0x07),
+					b'0'..=b'7' => {
+						let mut n = u32::from(c - b'0');
+						for _ in 0..2 {
+							match bytes.clone().next() {
+								Some(d @ b'0'..=b'7') => {
+									n = n * 8 + u32::from(d - b'0');
+									bytes.next();
+								}
+								_ => break,
+							}
+						}
+						// a null would end the string, so as in tic, it’s encoded as \200
+						out.push(if n == 0 { 0x80 } else { (n & 0xff) as u8 });
+					}
+					_ => out.push(c),
+				}
+			}
+			b'^' if prev != Some(b'%') => {
+				let c = bytes.next().ok_or("unterminated control character")?;
+				out.push(if c == b'?' { 0x7f } else { c & 0x1f });
+			}
+			_ => out.push(b),
+		}
+		prev = Some(b);
+	}
+	Ok(out)
+}
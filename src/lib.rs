@@ -22,20 +22,53 @@
 #![warn(missing_docs)]
 
 use std::{
-	borrow::Cow,
 	env,
 	io::{self, Write},
-	process::{Command, ExitStatus},
+	path::PathBuf,
+	process::ExitStatus,
+	time::Duration,
 };
 
 use terminfo::{
-	capability::{self, Expansion},
 	expand::{Context, Parameter},
-	Capability, Database, Value,
+	Database, Expand,
 };
 use thiserror::Error;
 use which::which;
 
+pub use actions::{Backend, ClearActions};
+pub use command::ExternalCommand;
+pub use entry::{terminfo_source, TerminfoEntry, TerminfoSource};
+pub use fallback::Fallback;
+pub use options::{Background, CommandLookup, Options, Padding, TermiosWhen};
+pub use overlay::Overlay;
+pub use plan::{ConsoleCall, Plan, Step};
+pub use strategy::{Capabilities, ClearStrategy, Registry};
+#[cfg(unix)]
+pub use termios::{TermiosProfile, TermiosState};
+
+/// Re-exported from [`nix`] for building a [`TermiosProfile`] and reading a [`TermiosState`].
+#[cfg(unix)]
+pub use nix::{
+	libc::cc_t,
+	sys::termios::{
+		ControlFlags, InputFlags, LocalFlags, OutputFlags, SpecialCharacterIndices, NCCS,
+	},
+};
+
+mod actions;
+mod command;
+mod entry;
+mod fallback;
+mod info;
+mod options;
+mod overlay;
+mod plan;
+pub mod sequences;
+mod strategy;
+#[cfg(unix)]
+mod termios;
+
 /// Ways to clear the screen.
 ///
 /// There isn’t a single way to clear the (terminal/console) screen. Not only are there several
@@ -53,6 +86,7 @@ use which::which;
 /// your context.
 ///
 /// For most cases, you should use [`ClearScreen::default()`] to select the most appropriate method.
+/// To run a command of your choosing instead, see [`ExternalCommand`].
 ///
 /// In any event, once a way is selected, call [`clear()`][ClearScreen::clear()] to apply it.
 ///
@@ -75,34 +109,84 @@ pub enum ClearScreen {
 
 	/// Looks up the `clear` capability in the terminfo (from the TERM env var), and applies it.
 	///
-	/// A non-hashed terminfo database is required (this is a [terminfo crate] limitation), such as
-	/// the one provided with ncurses.
+	/// The database is first read with the [terminfo crate]. If that fails, it’s read again with
+	/// this library’s own reader, which also supports the hashed `.db` databases used by the BSDs,
+	/// and looks in `/usr/share/misc/terminfo` as well. With the `embedded-terminfo` feature, entries
+	/// for the most common terminals are also bundled, and used if the system doesn’t have them.
+	///
+	/// If there is no entry for the terminal, the entries of similar terminals are tried instead:
+	/// see [`TerminfoEntry`].
 	///
 	/// [terminfo crate]: https://lib.rs/crates/terminfo
 	TerminfoScreen,
 
 	/// Looks up the `E3` (Erase Scrollback) capability in the terminfo (from the TERM env var), and applies it.
 	///
-	/// The same terminfo limitation applies as for [`TerminfoScreen`][ClearScreen::TerminfoScreen].
+	/// The terminfo database is read as for [`TerminfoScreen`][ClearScreen::TerminfoScreen].
 	TerminfoScrollback,
 
 	/// Performs a terminfo-driven terminal reset sequence.
 	///
-	/// This prints whichever are available of the **rs1**, **rs2**, **rs3**, and **rf** sequences.
-	/// If none of these are available, it prints whichever are available of the **is1**, **is2**,
-	/// **is3**, and **if** sequences. If none are available, an error is returned.
+	/// This prints the same sequences as ncurses’ `tput reset` does, in the same order:
+	///
+	/// - **rs1**, or **is1** if it’s not available;
+	/// - **rs2**, or **is2** if it’s not available;
+	/// - the sequences to reset the left and right margins to the terminal width, using the first
+	///   available of **mgc**, **smglr**, **smglp** and **smgrp**, or **smgl** and **smgr**;
+	/// - if the terminal’s initial tab stops (**it**) aren’t every 8 columns and the entry has both
+	///   **tbc** and **hts**, **tbc** to clear the tab stops, then if **it** is more than 1,
+	///   **hts** after every **it** spaces to set tab stops across the terminal width;
+	/// - the _contents_ of the file at the path given by **rf**, or **if** if it’s not available;
+	/// - **rs3**, or **is3** if it’s not available.
+	///
+	/// If none of these are available, an error is returned. The **iprog** capability, which names
+	/// a program to run, is not supported.
+	///
+	/// The terminal width is obtained from the `COLUMNS` environment variable, then from the
+	/// terminal itself, and finally from the **cols** capability.
 	///
 	/// This generally issues at least an `ESC c` sequence, which resets all terminal state to
 	/// default values, and then may issue more sequences to reset other things or enforce a
 	/// particular kind of state. See [`XtermReset`][ClearScreen::XtermReset] for a description of
 	/// what XTerm does, as an example.
 	///
-	/// Note that this is _not_ analogous to what `tput reset` does: to emulate that, issuing first
-	/// one of VtCooked/VtWellDone/WindowsCooked followed by this variant will come close.
+	/// Note that `tput reset` also resets the terminal modes: to emulate that fully, issue one of
+	/// VtCooked/VtWellDone/WindowsCooked before this variant.
 	///
 	/// The same terminfo limitation applies as for [`TerminfoScreen`][ClearScreen::TerminfoScreen].
 	TerminfoReset,
 
+	/// Performs a terminfo-driven recovery from a crashed TUI application.
+	///
+	/// This is the terminfo equivalent of [`VtRecover`][ClearScreen::VtRecover]: instead of
+	/// hard-coded sequences, it prints the terminal’s own sequences for whichever are available of:
+	///
+	/// - **rmcup** (exit_ca_mode), which leaves the alternate screen or “cursor addressing” mode;
+	/// - **cnorm** (cursor_normal), which makes the cursor visible and normal;
+	/// - **rmkx** (keypad_local), which takes the keypad and cursor keys out of application mode;
+	/// - **smam** (enter_am_mode), which turns automatic margins (line wrapping) back on, as TUIs
+	///   often turn it off with **rmam**;
+	/// - **rmso**, **rmul**, **ritm**, and **rmir**, which exit standout, underline, italics, and
+	///   insert modes;
+	/// - **sgr0** (exit_attribute_mode), which turns off all text attributes.
+	///
+	/// Missing capabilities are skipped; if none are available, an error is returned. To find out
+	/// which were applied, use [`ClearScreen::terminfo_recover_to()`].
+	///
+	/// The same terminfo limitation applies as for [`TerminfoScreen`][ClearScreen::TerminfoScreen].
+	TerminfoRecover,
+
+	/// Looks up the named string capability in the terminfo (from the TERM env var), and applies it.
+	///
+	/// This can be any capability, by its short or long name, including user-defined (extended)
+	/// capabilities such as **E3**, **Ss**, **Se**, **Sync**, or **XM**. It is expanded without
+	/// parameters; use [`expand_capability()`] if you need to pass some.
+	///
+	/// An error is returned if the capability isn’t available, or isn’t a string capability.
+	///
+	/// The same terminfo limitation applies as for [`TerminfoScreen`][ClearScreen::TerminfoScreen].
+	TerminfoCapability(&'static str),
+
 	/// Prints clear screen and scrollback sequence as if TERM=xterm.
 	///
 	/// This does not look up the correct sequence in the terminfo database, but rather prints:
@@ -139,10 +223,16 @@ pub enum ClearScreen {
 	/// Calls the command `tput clear`.
 	///
 	/// That command most likely does what [`Terminfo`][ClearScreen::Terminfo] does internally, but
-	/// may work better in some cases, such as when the terminfo database on the system is hashed or
-	/// in a non-standard location that the terminfo crate does not find.
+	/// may work better in some cases, such as when the terminfo database on the system is in a
+	/// non-standard location that this library does not find.
+	///
+	/// The output of the command is captured and written to the writer, like the other variants’,
+	/// and it’s given the terminal set with [`Options::term()`] if any, or else `TERM`. Some
+	/// terminals may need `tput` to write to them directly instead, which can be done with
+	/// [`Options::inherit_stdout()`].
 	///
 	/// However, it relies on the `tput` command being available, and on being able to run commands.
+	/// Where it’s looked for can be restricted with [`Options::command_lookup()`].
 	TputClear,
 
 	/// Calls the command `tput reset`.
@@ -157,8 +247,8 @@ pub enum ClearScreen {
 	/// [`TputClear`][ClearScreen::TputClear] does, but its internal mechanism is not known. Prefer
 	/// [`WindowsClear`][ClearScreen::WindowsClear] instead to avoid relying on an external command.
 	///
-	/// This will always attempt to run the command, regardless of compile target, which may have
-	/// unintended effects if the `cls` executable does something different on the platform.
+	/// This is only run on Windows: on other platforms, where a `cmd.exe` executable would be
+	/// something else entirely, [`Error::CommandRejected`] is returned.
 	Cls,
 
 	/// Sets the Windows Console to support VT escapes.
@@ -235,6 +325,37 @@ pub enum ClearScreen {
 	/// This is useful when recovering from a TUI application which crashed without resetting state.
 	VtLeaveAlt,
 
+	/// Prints a series of sequences to undo the terminal modes a TUI application commonly sets.
+	///
+	/// This is a more thorough version of [`VtLeaveAlt`][ClearScreen::VtLeaveAlt], for recovering
+	/// from a TUI application which crashed without resetting state. Unlike [`VtRis`][ClearScreen::VtRis]
+	/// and [`XtermReset`][ClearScreen::XtermReset], it does not reset the terminal entirely, so the
+	/// scrollback, window size, and other settings are kept. It prints:
+	///
+	/// - `CSI r` (Set Top and Bottom Margins, with defaults), which resets the scrolling region to
+	///   the entire screen. This moves the cursor to the home position.
+	/// - `CSI ?6l` (Reset Origin Mode), which makes cursor positions relative to the top left of
+	///   the screen instead of to the scrolling region. This also moves the cursor home.
+	/// - `CSI ?1049l` (Leave Alternate Screen), as in [`VtLeaveAlt`][ClearScreen::VtLeaveAlt].
+	///   This restores the cursor position saved when the alternate screen was entered.
+	/// - `CSI ?25h` (Show Cursor), which makes the cursor visible again.
+	/// - `CSI ?1000;1002;1003;1006l` (Disable Mouse Tracking), which turns off the X11 mouse
+	///   reporting modes (normal, button-event, and any-event tracking) and the SGR extended
+	///   encoding. Otherwise, moving or clicking the mouse types garbage into the shell.
+	/// - `CSI ?2004l` (Disable Bracketed Paste), which stops the terminal from wrapping pasted
+	///   text in `CSI 200~` and `CSI 201~` markers.
+	/// - `CSI ?1004l` (Disable Focus Events), which stops the terminal from sending `CSI I` and
+	///   `CSI O` when the window gains or loses focus.
+	/// - `CSI ?1l` (Normal Cursor Keys), which makes the arrow keys send `CSI A` etc instead of
+	///   the application mode `SS3 A` etc.
+	/// - `ESC >` (Set Key Pad to Normal), as in [`XtermReset`][ClearScreen::XtermReset].
+	/// - `CSI m` (Select Graphic Rendition, with defaults), which resets text attributes like
+	///   colours, bold, underline, and reverse video.
+	///
+	/// Unknown modes are ignored by terminals, so this is safe to use on terminals which don’t
+	/// support some of these. It does not clear the screen: combine with another variant for that.
+	VtRecover,
+
 	/// Sets the terminal to cooked mode.
 	///
 	/// This attempts to switch the terminal to “cooked” mode, which can be thought of as the
@@ -310,8 +431,21 @@ pub enum ClearScreen {
 	/// - Local ICANON set: enable canonical mode (see [`VtCooked`][ClearScreen::VtCooked]).
 	/// - Local ISIG set: when Ctrl-C, Ctrl-Q, etc are received, send the appropriate signal.
 	///
+	/// Unlike [`VtCooked`][ClearScreen::VtCooked], this discards any pending input before applying
+	/// the configuration (`tcflush`), as a crashed program often leaves garbage like mouse reports
+	/// in the input queue, which would otherwise be read by the shell as keystrokes. The change is
+	/// still made immediately (`TCSANOW`): waiting for pending output to be written first would
+	/// hang on a terminal stopped with XOFF. This can be changed with [`Options`].
+	///
 	/// Does nothing on non-Unix targets.
 	VtWellDone,
+
+	/// Performs a combination of independent actions with a given backend.
+	///
+	/// See [`ClearActions`] and [`Backend`]. If the backend can’t do all of the actions, nothing is
+	/// done, and [`Error::UnsupportedActions`] is returned. Most other variants are equivalent to
+	/// such a combination, which [`actions()`][ClearScreen::actions()] returns.
+	Actions(ClearActions, Backend),
 }
 
 impl Default for ClearScreen {
@@ -331,17 +465,15 @@ impl Default for ClearScreen {
 	/// See the [TERMINALS.md file in the repo][TERMINALS.md] for research on many terminals as well
 	/// as the current result of this function for each terminal.
 	///
+	/// To fall back to another method if this one fails, use [`Fallback::default()`] instead.
+	///
+	/// This only selects among the built-in variants: strategies added to a [`Registry`] are not
+	/// considered. To select those for the terminals they handle, and otherwise fall back to this
+	/// function, use [`Registry::for_env()`] instead.
+	///
 	/// [TERMINALS.md]: https://github.com/watchexec/clearscreen/blob/main/TERMINALS.md
 	fn default() -> Self {
-		use env::var;
-		use std::ffi::OsStr;
-
-		fn varfull(key: impl AsRef<OsStr>) -> bool {
-			var(key).map_or(false, |s| !s.is_empty())
-		}
-
-		let term = var("TERM").ok();
-		let term = term.as_ref();
+		let term = env::var("TERM").ok();
 
 		if cfg!(windows) {
 			return if is_microsoft_terminal() {
@@ -357,74 +489,141 @@ impl Default for ClearScreen {
 			};
 		}
 
-		if let Some(term) = term {
-			// These VTE-based terminals support CSI 3J but their own terminfos don’t have E3
-			if (term.starts_with("gnome")
-				&& varfull("GNOME_TERMINAL_SCREEN")
-				&& varfull("GNOME_TERMINAL_SERVICE"))
-				|| term == "xfce"
-				|| term.contains("termite")
-			{
-				return Self::XtermClear;
-			}
+		term.map_or(Self::XtermClear, |term| Self::select(&term, true))
+	}
+}
 
-			// - SyncTERM does support the XtermClear sequence but does not clear the scrollback,
-			// and does not have a terminfo, so VtRis is the only option.
-			// - rxvt, when using its own terminfos, erases the screen instead of clearing and
-			// doesn’t clear scrollback. It supports and behave properly for the entire XtermClear
-			// sequence, but it also does the right thing with VtRis, and that seems more reliable.
-			// - Other variants of (u)rxvt do the same.
-			// - Kitty does as rxvt does here.
-			// - Tess does support the XtermClear sequence but has a weird scrollbar behaviour,
-			// which does not happen with VtRis.
-			// - Zutty does not support E3, and erases the buffer on clear like rxvt, but does work
-			// properly with VtRis.
-			// - Same behaviour with the multiplexer Zellij.
-			if term == "syncterm"
-				|| term.contains("rxvt")
-				|| term.contains("kitty")
-				|| var("CHROME_DESKTOP").map_or(false, |cd| cd == "tess.desktop")
-				|| varfull("ZUTTY_VERSION")
-				|| varfull("ZELLIJ")
-			{
-				return Self::VtRis;
-			}
+impl ClearScreen {
+	/// Makes a best guess as how to clear the screen of the named terminal.
+	///
+	/// This is like [`ClearScreen::default()`], but for a terminal which may not be the one the
+	/// process runs under, for example to render output for it. Only the name and its terminfo
+	/// entry are used: neither the environment nor the Windows console are inspected for hints as
+	/// to which terminal is really in use.
+	///
+	/// To produce the output of the terminfo variants for that terminal, also pass its name with
+	/// [`Options::term()`].
+	pub fn for_term(term: &str) -> Self {
+		Self::select(term, false)
+	}
 
-			// - screen supports CSI 3J only within the XtermClear sequence, without E3 capability.
-			// - Konsole handles CSI 3J correctly only within the XtermClear sequence.
-			// - assume tmux TERMs are only used within tmux, and avoid the requirement for a functioning terminfo then
-			if term.starts_with("screen") || term.starts_with("konsole") || term.starts_with("tmux") {
-				return Self::XtermClear;
-			}
+	fn select(term: &str, detect: bool) -> Self {
+		let var = |key: &str| env::var(key).ok().filter(|_| detect);
+		let varfull = |key: &str| detect && varfull(key);
 
-			// Default xterm* terminfo on macOS does not include E3, but many terminals support it.
-			if cfg!(target_os = "macos")
-				&& term.starts_with("xterm")
-				&& Database::from_env()
-					.map(|info| info.get::<ResetScrollback>().is_none())
-					.unwrap_or(true)
-			{
-				return Self::XtermClear;
-			}
+		// These VTE-based terminals support CSI 3J but their own terminfos don’t have E3
+		if (term.starts_with("gnome")
+			&& varfull("GNOME_TERMINAL_SCREEN")
+			&& varfull("GNOME_TERMINAL_SERVICE"))
+			|| term == "xfce"
+			|| term.contains("termite")
+		{
+			return Self::XtermClear;
+		}
 
-			if !term.is_empty() && Database::from_env().is_ok() {
-				return Self::Terminfo;
-			}
+		// - SyncTERM does support the XtermClear sequence but does not clear the scrollback,
+		// and does not have a terminfo, so VtRis is the only option.
+		// - rxvt, when using its own terminfos, erases the screen instead of clearing and
+		// doesn’t clear scrollback. It supports and behave properly for the entire XtermClear
+		// sequence, but it also does the right thing with VtRis, and that seems more reliable.
+		// - Other variants of (u)rxvt do the same.
+		// - Kitty does as rxvt does here.
+		// - Tess does support the XtermClear sequence but has a weird scrollbar behaviour,
+		// which does not happen with VtRis.
+		// - Zutty does not support E3, and erases the buffer on clear like rxvt, but does work
+		// properly with VtRis.
+		// - Same behaviour with the multiplexer Zellij.
+		if term == "syncterm"
+			|| term.contains("rxvt")
+			|| term.contains("kitty")
+			|| var("CHROME_DESKTOP").is_some_and(|cd| cd == "tess.desktop")
+			|| varfull("ZUTTY_VERSION")
+			|| varfull("ZELLIJ")
+		{
+			return Self::VtRis;
+		}
+
+		// - screen supports CSI 3J only within the XtermClear sequence, without E3 capability.
+		// - Konsole handles CSI 3J correctly only within the XtermClear sequence.
+		// - assume tmux TERMs are only used within tmux, and avoid the requirement for a functioning terminfo then
+		if term.starts_with("screen") || term.starts_with("konsole") || term.starts_with("tmux") {
+			return Self::XtermClear;
+		}
+
+		let info = TerminfoEntry::resolve(term).map(|entry| entry.database);
+
+		// Default xterm* terminfo on macOS does not include E3, but many terminals support it.
+		if cfg!(target_os = "macos")
+			&& term.starts_with("xterm")
+			&& info
+				.as_ref()
+				.map(|info| info::string_cap(&info::Info::new(info), "E3").is_none())
+				.unwrap_or(true)
+		{
+			return Self::XtermClear;
+		}
+
+		if !term.is_empty() && info.is_ok() {
+			return Self::Terminfo;
 		}
 
 		Self::XtermClear
 	}
 }
 
-const ESC: &[u8] = b"\x1b";
-const CSI: &[u8] = b"\x1b[";
-const RIS: &[u8] = b"c";
+impl ClearScreen {
+	/// The combination of [`ClearActions`] and [`Backend`] which this variant is equivalent to.
+	///
+	/// Returns `None` for variants which aren’t such a combination, like
+	/// [`TerminfoCapability`][ClearScreen::TerminfoCapability], [`VtRis`][ClearScreen::VtRis],
+	/// or [`Cls`][ClearScreen::Cls].
+	///
+	/// For [`VtWellDone`][ClearScreen::VtWellDone] and [`WindowsCooked`][ClearScreen::WindowsCooked],
+	/// the equivalent is only on their own platform, as
+	/// [`RESTORE_TERMIOS`][ClearActions::RESTORE_TERMIOS] does whichever is native.
+	pub fn actions(self) -> Option<(ClearActions, Backend)> {
+		const HOME_SCREEN: ClearActions =
+			ClearActions::HOME_CURSOR.union(ClearActions::ERASE_SCREEN);
+		const ALL: ClearActions = HOME_SCREEN.union(ClearActions::ERASE_SCROLLBACK);
+
+		Some(match self {
+			Self::Terminfo => (ALL, Backend::Terminfo),
+			Self::TerminfoScreen => (HOME_SCREEN, Backend::Terminfo),
+			Self::TerminfoScrollback => (ClearActions::ERASE_SCROLLBACK, Backend::Terminfo),
+			Self::TerminfoReset => (ClearActions::FULL_RESET, Backend::Terminfo),
+			Self::TerminfoRecover => (ClearActions::RESET_MODES, Backend::Terminfo),
+			Self::XtermClear => (ALL, Backend::Xterm),
+			Self::XtermReset => (ClearActions::FULL_RESET, Backend::Xterm),
+			Self::TputClear => (ALL, Backend::Tput),
+			Self::TputReset => (ClearActions::FULL_RESET, Backend::Tput),
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleClear => (ALL, Backend::WindowsConsole),
+			Self::WindowsCooked => (ClearActions::RESTORE_TERMIOS, Backend::WindowsConsole),
+			Self::VtLeaveAlt => (ClearActions::LEAVE_ALT_SCREEN, Backend::Xterm),
+			Self::VtRecover => (ClearActions::RESET_MODES, Backend::Xterm),
+			Self::VtWellDone => (ClearActions::RESTORE_TERMIOS, Backend::Xterm),
+			Self::Actions(actions, backend) => (actions, backend),
+			_ => return None,
+		})
+	}
+}
+
+fn varfull(key: &str) -> bool {
+	env::var(key).is_ok_and(|s| !s.is_empty())
+}
 
 impl ClearScreen {
 	/// Performs the clearing action, printing to stdout.
 	pub fn clear(self) -> Result<(), Error> {
-		let mut stdout = io::stdout();
-		self.clear_to(&mut stdout)
+		self.clear_with(&Options::default())
+	}
+
+	/// Performs the clearing action with the given [`Options`], printing to stdout.
+	pub fn clear_with(self, options: &Options) -> Result<(), Error> {
+		unix::foreground_output(options, || {
+			let mut stdout = io::stdout();
+			self.clear_to_with(&mut stdout, options)
+		})
 	}
 
 	/// Performs the clearing action, printing to a given writer.
@@ -434,182 +633,184 @@ impl ClearScreen {
 	/// VtWellDone variants do.
 	///
 	/// For normal use, prefer [`clear()`].
-	pub fn clear_to(self, mut w: &mut impl Write) -> Result<(), Error> {
+	pub fn clear_to(self, w: &mut impl Write) -> Result<(), Error> {
+		self.clear_to_with(w, &Options::default())
+	}
+
+	/// Performs the clearing action using the given terminfo database, printing to a given writer.
+	///
+	/// The terminfo variants use this database instead of looking up the one for the current
+	/// terminal, and so produce the output for whichever terminal it describes. Other variants are
+	/// unaffected. See also [`Options::database()`] and [`Options::term()`].
+	pub fn clear_to_with_db(self, info: &Database, w: &mut impl Write) -> Result<(), Error> {
+		self.clear_to_with(w, &Options::default().database(info.clone()))
+	}
+
+	/// Performs the clearing action with the given [`Options`], printing to a given writer.
+	///
+	/// See [`clear_to()`][ClearScreen::clear_to()] for caveats.
+	pub fn clear_to_with(self, w: &mut impl Write, options: &Options) -> Result<(), Error> {
+		match self {
+			Self::Terminfo
+			| Self::TerminfoScreen
+			| Self::TerminfoScrollback
+			| Self::TerminfoReset
+			| Self::TerminfoRecover
+			| Self::TerminfoCapability(_) => self.terminfo_to_with(&mut info::Sleeping(w), options)?,
+			Self::XtermClear => {
+				w.write_all(sequences::XTERM_CLEAR.as_bytes())?;
+				w.flush()?;
+			}
+			Self::XtermReset => {
+				w.write_all(sequences::XTERM_RESET.as_bytes())?;
+				w.flush()?;
+			}
+			Self::TputClear => command::tput(&["clear"], options, w)?,
+			Self::TputReset => command::tput(&["reset"], options, w)?,
+			Self::Cls => command::cls(options)?,
+			Self::WindowsVt => win::vt()?,
+			Self::WindowsVtClear => {
+				let vtres = win::vt();
+				Self::XtermClear.clear_to_with(w, options)?;
+				vtres?;
+			}
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleClear => win::clear()?,
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleBlank => win::blank()?,
+			Self::WindowsCooked => win::cooked()?,
+			Self::VtRis => {
+				w.write_all(sequences::RIS.as_bytes())?;
+				w.flush()?;
+			}
+			Self::VtLeaveAlt => {
+				w.write_all(sequences::LEAVE_ALT_SCREEN.as_bytes())?;
+				w.flush()?;
+			}
+			Self::VtRecover => {
+				w.write_all(sequences::VT_RECOVER.as_bytes())?;
+				w.flush()?;
+			}
+			Self::VtCooked => unix::vt_cooked(options)?,
+			Self::VtWellDone => unix::vt_well_done(options)?,
+			Self::Actions(actions, backend) => actions.perform(backend, w, options)?,
+		}
+
+		Ok(())
+	}
+
+	/// Performs the terminfo variants, writing to an [`Output`][info::Output], which is either a
+	/// writer or a [`Plan`] being recorded. Other variants do nothing.
+	pub(crate) fn terminfo_to_with(
+		self,
+		w: &mut impl info::Output,
+		options: &Options,
+	) -> Result<(), Error> {
 		match self {
 			Self::Terminfo => {
-				let info = Database::from_env()?;
+				let info = info::load(options)?;
+				let delays = info::Delays::new(&info, options, info::lines(&info));
 				let mut ctx = Context::default();
 
-				if let Some(seq) = info.get::<capability::ClearScreen>() {
-					seq.expand().with(&mut ctx).to(&mut w)?;
+				if info::write_cap(&info, "clear", &mut ctx, &delays, w)? {
 					w.flush()?;
 				} else {
-					return Err(Error::TerminfoCap("clear"));
+					return Err(info.missing("clear"));
 				}
 
-				if let Some(seq) = info.get::<ResetScrollback>() {
-					seq.expand().with(&mut ctx).to(&mut w)?;
+				if info::write_cap(&info, "E3", &mut ctx, &delays, w)? {
 					w.flush()?;
 				}
 			}
 			Self::TerminfoScreen => {
-				let info = Database::from_env()?;
-				if let Some(seq) = info.get::<capability::ClearScreen>() {
-					seq.expand().to(&mut w)?;
+				let info = info::load(options)?;
+				let delays = info::Delays::new(&info, options, info::lines(&info));
+				if info::write_cap(&info, "clear", &mut Context::default(), &delays, w)? {
 					w.flush()?;
 				} else {
-					return Err(Error::TerminfoCap("clear"));
+					return Err(info.missing("clear"));
 				}
 			}
 			Self::TerminfoScrollback => {
-				let info = Database::from_env()?;
-				if let Some(seq) = info.get::<ResetScrollback>() {
-					seq.expand().to(&mut w)?;
+				let info = info::load(options)?;
+				let delays = info::Delays::new(&info, options, info::lines(&info));
+				if info::write_cap(&info, "E3", &mut Context::default(), &delays, w)? {
 					w.flush()?;
 				} else {
-					return Err(Error::TerminfoCap("E3"));
+					return Err(info.missing("E3"));
 				}
 			}
 			Self::TerminfoReset => {
-				let info = Database::from_env()?;
-				let mut ctx = Context::default();
-				let mut reset = false;
-
-				if let Some(seq) = info.get::<capability::Reset1String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::Reset2String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::Reset3String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::ResetFile>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-
-				w.flush()?;
-
-				if reset {
-					return Ok(());
-				}
-
-				if let Some(seq) = info.get::<capability::Init1String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::Init2String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::Init3String>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-				if let Some(seq) = info.get::<capability::InitFile>() {
-					reset = true;
-					seq.expand().with(&mut ctx).to(&mut w)?;
-				}
-
-				w.flush()?;
-
-				if !reset {
-					return Err(Error::TerminfoCap("reset"));
-				}
-			}
-			Self::XtermClear => {
-				const CURSOR_HOME: &[u8] = b"H";
-				const ERASE_SCREEN: &[u8] = b"2J";
-				const ERASE_SCROLLBACK: &[u8] = b"3J";
-
-				w.write_all(CSI)?;
-				w.write_all(CURSOR_HOME)?;
-
-				w.write_all(CSI)?;
-				w.write_all(ERASE_SCREEN)?;
-
-				w.write_all(CSI)?;
-				w.write_all(ERASE_SCROLLBACK)?;
-
-				w.flush()?;
-			}
-			Self::XtermReset => {
-				const STR: &[u8] = b"!p";
-				const RESET_WIDTH_AND_SCROLL: &[u8] = b"?3;4l";
-				const RESET_REPLACE: &[u8] = b"4l";
-				const RESET_KEYPAD: &[u8] = b">";
-				const RESET_MARGINS: &[u8] = b"?69l";
-
-				w.write_all(ESC)?;
-				w.write_all(RIS)?;
-
-				w.write_all(CSI)?;
-				w.write_all(STR)?;
-
-				w.write_all(CSI)?;
-				w.write_all(RESET_WIDTH_AND_SCROLL)?;
-
-				w.write_all(CSI)?;
-				w.write_all(RESET_REPLACE)?;
-
-				w.write_all(ESC)?;
-				w.write_all(RESET_KEYPAD)?;
-
-				w.write_all(CSI)?;
-				w.write_all(RESET_MARGINS)?;
-
-				w.flush()?;
+				let info = info::load(options)?;
+				info::reset(&info, options, w)?;
 			}
-			Self::TputClear => {
-				let status = Command::new("tput").arg("clear").status()?;
-				if !status.success() {
-					return Err(Error::Command("tput clear", status));
-				}
+			Self::TerminfoRecover => {
+				let info = info::load(options)?;
+				info::recover(&info, options, &[], w)?;
 			}
-			Self::TputReset => {
-				let status = Command::new("tput").arg("reset").status()?;
-				if !status.success() {
-					return Err(Error::Command("tput reset", status));
-				}
-			}
-			Self::Cls => {
-				let status = Command::new("cmd.exe").arg("/C").arg("cls").status()?;
-				if !status.success() {
-					return Err(Error::Command("cls", status));
+			Self::TerminfoCapability(name) => {
+				let info = info::load(options)?;
+				let delays = info::Delays::new(&info, options, 1);
+				if info::write_cap(&info, name, &mut Context::default(), &delays, w)? {
+					w.flush()?;
+				} else {
+					return Err(info.missing(name));
 				}
 			}
-			Self::WindowsVt => win::vt()?,
-			Self::WindowsVtClear => {
-				let vtres = win::vt();
-				Self::XtermClear.clear_to(w)?;
-				vtres?;
-			}
-			#[cfg(feature = "windows-console")]
-			Self::WindowsConsoleClear => win::clear()?,
-			#[cfg(feature = "windows-console")]
-			Self::WindowsConsoleBlank => win::blank()?,
-			Self::WindowsCooked => win::cooked()?,
-			Self::VtRis => {
-				w.write_all(ESC)?;
-				w.write_all(RIS)?;
-				w.flush()?;
-			}
-			Self::VtLeaveAlt => {
-				const LEAVE_ALT: &[u8] = b"?1049l";
-				w.write_all(CSI)?;
-				w.write_all(LEAVE_ALT)?;
-				w.flush()?;
-			}
-			Self::VtCooked => unix::vt_cooked()?,
-			Self::VtWellDone => unix::vt_well_done()?,
+			_ => {}
 		}
 
 		Ok(())
 	}
+
+	/// Performs the [`TerminfoRecover`][ClearScreen::TerminfoRecover] action, printing to a given
+	/// writer, and returns the names of the capabilities which were applied.
+	pub fn terminfo_recover_to(w: &mut impl Write) -> Result<Vec<&'static str>, Error> {
+		Self::terminfo_recover_to_with(w, &Options::default())
+	}
+
+	/// Performs the [`TerminfoRecover`][ClearScreen::TerminfoRecover] action with the given
+	/// [`Options`], printing to a given writer, and returns the names of the capabilities which
+	/// were applied.
+	pub fn terminfo_recover_to_with(
+		w: &mut impl Write,
+		options: &Options,
+	) -> Result<Vec<&'static str>, Error> {
+		let info = info::load(options)?;
+		info::recover(&info, options, &[], &mut info::Sleeping(w))
+	}
+}
+
+/// Looks up a string capability by name in a terminfo database, and expands it.
+///
+/// The capability can be given by its short or long name, and can be a user-defined (extended)
+/// capability, which the terminfo crate doesn’t have types for. Up to nine parameters can be given,
+/// as in the terminfo format; further parameters are ignored.
+///
+/// Returns `None` if the capability isn’t available, or isn’t a string capability. Delays, as in
+/// `$<5>`, are left in the output as-is.
+///
+/// ```no_run
+/// # use clearscreen::expand_capability;
+/// let info = terminfo::Database::from_env()?;
+/// if let Some(seq) = expand_capability(&info, "Ss", &[2.into()])? {
+///     // `seq` sets the cursor to a steady block
+/// }
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+pub fn expand_capability(
+	info: &Database,
+	name: &str,
+	params: &[Parameter],
+) -> Result<Option<Vec<u8>>, Error> {
+	let info = info::Info::new(info);
+	let Some(cap) = info::string_cap(&info, name) else {
+		return Ok(None);
+	};
+
+	let mut seq = Vec::new();
+	cap.expand(&mut seq, params, &mut Context::default())?;
+	Ok(Some(seq))
 }
 
 /// Shorthand for `ClearScreen::default().clear()`.
@@ -682,8 +883,59 @@ pub enum Error {
 	Io(#[from] io::Error),
 
 	/// A non-success exit status from a command.
-	#[error("{0}: {1}")]
-	Command(&'static str, ExitStatus),
+	#[error("{}: {status}{}", argv.join(" "), stderr_note(stderr))]
+	Command {
+		/// The command and its arguments.
+		argv: Vec<String>,
+
+		/// The exit status.
+		status: ExitStatus,
+
+		/// What the command printed to stderr.
+		stderr: String,
+	},
+
+	/// A command wasn’t found.
+	#[error("{program}: command not found")]
+	CommandNotFound {
+		/// The program, as it was looked for.
+		program: String,
+	},
+
+	/// A command didn’t finish in time, and was killed.
+	///
+	/// See [`Options::command_timeout()`].
+	#[error("{}: timed out after {timeout:?}", argv.join(" "))]
+	CommandTimeout {
+		/// The command and its arguments.
+		argv: Vec<String>,
+
+		/// The timeout.
+		timeout: Duration,
+	},
+
+	/// An external command was refused, or not found, under the rules set with
+	/// [`Options::command_lookup()`].
+	#[error("refusing to run {program}: {reason}")]
+	CommandRejected {
+		/// The program, as it was given.
+		program: String,
+
+		/// Why it was refused.
+		reason: String,
+	},
+
+	/// A backend can’t perform some [`ClearActions`].
+	///
+	/// See [`ClearScreen::Actions`].
+	#[error("the {backend} backend can’t perform {actions}")]
+	UnsupportedActions {
+		/// The actions which can’t be performed.
+		actions: ClearActions,
+
+		/// The backend.
+		backend: Backend,
+	},
 
 	/// Any nix (libc) error.
 	#[cfg(unix)]
@@ -694,60 +946,178 @@ pub enum Error {
 	#[error(transparent)]
 	Terminfo(#[from] terminfo::Error),
 
-	/// A missing terminfo capability.
-	#[error("required terminfo capability not available: {0}")]
-	TerminfoCap(&'static str),
+	/// No terminfo entry was found for a terminal, nor for any of its fallbacks.
+	///
+	/// See [`TerminfoEntry`].
+	#[error(
+		"no terminfo entry found for {term} (tried: {}; searched: {})",
+		tried.join(", "),
+		searched.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ")
+	)]
+	TerminfoNotFound {
+		/// The name of the terminal.
+		term: String,
+
+		/// The names of the entries which were tried, in order.
+		tried: Vec<String>,
+
+		/// The locations which were searched, in order.
+		///
+		/// These are the directories of the terminfo search path, whether they exist or not. In each,
+		/// both the directory tree and a hashed database alongside are looked at.
+		searched: Vec<PathBuf>,
+	},
+
+	/// An invalid capability overlay was given to parse an [`Overlay`] from.
+	#[error("invalid capability overlay on line {line}: {reason}")]
+	OverlayFormat {
+		/// The line number, starting from 1.
+		line: usize,
+
+		/// What is wrong with the line.
+		reason: &'static str,
+	},
+
+	/// A terminfo capability missing from the entry for a terminal.
+	#[error(
+		"required terminfo capability not available: {cap} ({})",
+		cap_context(term, entry.as_deref(), location.as_ref())
+	)]
+	TerminfoCapNotFound {
+		/// The name of the capability.
+		cap: &'static str,
+
+		/// The name of the terminal.
+		term: String,
+
+		/// The name of the entry which was used, which may be a fallback.
+		///
+		/// This is `None` when the terminfo database was given directly, with
+		/// [`Options::database()`].
+		entry: Option<String>,
+
+		/// Where the entry was read from, if it was looked up.
+		location: Option<TerminfoSource>,
+	},
+
+	/// A terminfo entry file was found for a terminal, but couldn’t be read.
+	///
+	/// This is only returned if no other entry could be used instead.
+	#[error("invalid terminfo entry for {term}: {}", path.display())]
+	TerminfoInvalid {
+		/// The name of the terminal.
+		term: String,
+
+		/// The path of the file, which may be a hashed database.
+		path: PathBuf,
+	},
+
+	/// The process is not in the terminal’s foreground process group.
+	///
+	/// See [`Options::background()`].
+	#[cfg(unix)]
+	#[error("not in the terminal’s foreground process group")]
+	NotForeground,
+
+	/// An invalid `stty -g` string was given to parse a [`TermiosState`] from.
+	#[cfg(unix)]
+	#[error("invalid stty -g format: {0}")]
+	SttyFormat(&'static str),
 
 	/// A null-pointer error.
 	#[error("encountered a null pointer while reading {0}")]
 	NullPtr(&'static str),
 }
 
+impl Error {
+	/// The kind of error, as used by [`Fallback`] to decide whether to try the next strategy.
+	pub fn kind(&self) -> ErrorKind {
+		match self {
+			Self::TerminfoNotFound { .. }
+			| Self::TerminfoInvalid { .. }
+			| Self::Terminfo(terminfo::Error::NotFound) => ErrorKind::TerminfoNotFound,
+			Self::TerminfoCapNotFound { .. } => ErrorKind::CapabilityNotFound,
+			Self::CommandNotFound { .. } | Self::CommandRejected { .. } => {
+				ErrorKind::CommandNotFound
+			}
+			Self::Command { .. } | Self::CommandTimeout { .. } => ErrorKind::CommandFailed,
+			Self::UnsupportedActions { .. } => ErrorKind::Unsupported,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+/// Kinds of [`Error`][enum@Error], by what went wrong rather than where.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+	/// No terminfo entry could be found or read for the terminal, or `TERM` isn’t set.
+	TerminfoNotFound,
+
+	/// The terminfo entry doesn’t have a capability which is needed.
+	CapabilityNotFound,
+
+	/// An external command wasn’t found, or was refused by [`Options::command_lookup()`].
+	CommandNotFound,
+
+	/// An external command failed, or timed out.
+	CommandFailed,
+
+	/// The actions can’t be performed by the backend.
+	Unsupported,
+
+	/// Anything else, such as I/O errors.
+	Other,
+}
+
+fn cap_context(term: &str, entry: Option<&str>, location: Option<&TerminfoSource>) -> String {
+	match (entry, location) {
+		(Some(entry), Some(location)) => {
+			format!("in terminfo entry {entry} for {term}, from {location}")
+		}
+		(Some(entry), None) => format!("in terminfo entry {entry} for {term}"),
+		(None, _) => format!("for {term}"),
+	}
+}
+
+fn stderr_note(stderr: &str) -> String {
+	let stderr = stderr.trim();
+	if stderr.is_empty() {
+		String::new()
+	} else {
+		format!(" ({stderr})")
+	}
+}
+
 #[cfg(unix)]
 mod unix {
-	use super::Error;
+	use super::{Background, Error, Options, TermiosProfile, TermiosWhen};
 
 	use nix::{
-		sys::termios::{
-			tcgetattr, tcsetattr, ControlFlags, InputFlags, LocalFlags, OutputFlags,
-			SetArg::TCSANOW, Termios,
+		libc::{ioctl, winsize, TIOCGWINSZ},
+		sys::{
+			signal::{SigSet, SigmaskHow, Signal},
+			termios::{
+				cfgetospeed, tcflush, tcgetattr, tcsetattr, BaudRate, ControlFlags, FlushArg,
+				InputFlags, LocalFlags, OutputFlags, SetArg, Termios,
+			},
 		},
-		unistd::isatty,
+		unistd::{getpgrp, isatty, tcgetpgrp, Pid},
 	};
 
-	use std::{fs::OpenOptions, io::stdin, os::fd::AsFd, os::unix::prelude::AsRawFd};
-
-	pub(crate) fn vt_cooked() -> Result<(), Error> {
-		write_termios(|t| {
-			t.input_flags.insert(
-				InputFlags::BRKINT
-					| InputFlags::ICRNL | InputFlags::IGNPAR
-					| InputFlags::ISTRIP | InputFlags::IXON,
-			);
-			t.output_flags.insert(OutputFlags::OPOST);
-			t.local_flags.insert(LocalFlags::ICANON | LocalFlags::ISIG);
-		})
-	}
+	use std::{
+		fs::OpenOptions,
+		io::{stdin, stdout},
+		os::fd::{AsFd, BorrowedFd},
+		os::unix::prelude::AsRawFd,
+	};
 
-	pub(crate) fn vt_well_done() -> Result<(), Error> {
-		write_termios(|t| {
-			let mut inserts =
-				InputFlags::BRKINT
-					| InputFlags::ICRNL | InputFlags::IGNPAR
-					| InputFlags::IMAXBEL
-					| InputFlags::ISTRIP | InputFlags::IXON;
-
-			#[cfg(any(target_os = "android", target_os = "linux", target_os = "macos"))]
-			{
-				inserts |= InputFlags::IUTF8;
-			}
+	pub(crate) fn vt_cooked(options: &Options) -> Result<(), Error> {
+		TermiosProfile::cooked().apply_with(options)
+	}
 
-			t.input_flags.insert(inserts);
-			t.output_flags
-				.insert(OutputFlags::ONLCR | OutputFlags::OPOST);
-			t.control_flags.insert(ControlFlags::CREAD);
-			t.local_flags.insert(LocalFlags::ICANON | LocalFlags::ISIG);
-		})
+	pub(crate) fn vt_well_done(options: &Options) -> Result<(), Error> {
+		TermiosProfile::well_done().apply_with(options)
 	}
 
 	fn reset_termios(t: &mut Termios) {
@@ -757,23 +1127,213 @@ mod unix {
 		t.local_flags.remove(LocalFlags::all());
 	}
 
-	fn write_termios(f: impl Fn(&mut Termios)) -> Result<(), Error> {
-		if isatty(stdin().as_raw_fd())? {
-			let mut t = tcgetattr(stdin().as_fd())?;
-			reset_termios(&mut t);
-			f(&mut t);
-			tcsetattr(stdin().as_fd(), TCSANOW, &t)?;
+	fn with_tty<T>(f: impl FnOnce(BorrowedFd) -> Result<T, Error>) -> Result<T, Error> {
+		let stdin = stdin();
+		if isatty(stdin.as_raw_fd())? {
+			f(stdin.as_fd())
 		} else {
 			let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
-			let fd = tty.as_fd();
+			f(tty.as_fd())
+		}
+	}
+
+	pub(crate) fn baud_rate() -> Option<u32> {
+		rate(cfgetospeed(&read_termios().ok()?))
+	}
 
-			let mut t = tcgetattr(fd)?;
-			reset_termios(&mut t);
-			f(&mut t);
-			tcsetattr(fd, TCSANOW, &t)?;
+	// the BSDs and macOS use the rate itself as the speed
+	#[cfg(any(
+		target_os = "dragonfly",
+		target_os = "freebsd",
+		target_os = "netbsd",
+		target_os = "openbsd",
+		target_vendor = "apple"
+	))]
+	#[allow(clippy::unnecessary_wraps)]
+	fn rate(speed: BaudRate) -> Option<u32> {
+		Some(speed.into())
+	}
+
+	#[cfg(not(any(
+		target_os = "dragonfly",
+		target_os = "freebsd",
+		target_os = "netbsd",
+		target_os = "openbsd",
+		target_vendor = "apple"
+	)))]
+	fn rate(speed: BaudRate) -> Option<u32> {
+		Some(match speed {
+			BaudRate::B0 => 0,
+			BaudRate::B50 => 50,
+			BaudRate::B75 => 75,
+			BaudRate::B110 => 110,
+			BaudRate::B134 => 134,
+			BaudRate::B150 => 150,
+			BaudRate::B200 => 200,
+			BaudRate::B300 => 300,
+			BaudRate::B600 => 600,
+			BaudRate::B1200 => 1200,
+			BaudRate::B1800 => 1800,
+			BaudRate::B2400 => 2400,
+			BaudRate::B4800 => 4800,
+			BaudRate::B9600 => 9600,
+			BaudRate::B19200 => 19200,
+			BaudRate::B38400 => 38400,
+			#[cfg(not(target_os = "aix"))]
+			BaudRate::B57600 => 57600,
+			#[cfg(not(target_os = "aix"))]
+			BaudRate::B115200 => 115_200,
+			#[cfg(any(target_os = "illumos", target_os = "solaris"))]
+			BaudRate::B153600 => 153_600,
+			#[cfg(not(target_os = "aix"))]
+			BaudRate::B230400 => 230_400,
+			#[cfg(any(target_os = "illumos", target_os = "solaris"))]
+			BaudRate::B307200 => 307_200,
+			#[cfg(any(
+				target_os = "android",
+				target_os = "linux",
+				target_os = "illumos",
+				target_os = "solaris"
+			))]
+			BaudRate::B460800 => 460_800,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B500000 => 500_000,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B576000 => 576_000,
+			#[cfg(any(
+				target_os = "android",
+				target_os = "linux",
+				target_os = "illumos",
+				target_os = "solaris"
+			))]
+			BaudRate::B921600 => 921_600,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B1000000 => 1_000_000,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B1152000 => 1_152_000,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B1500000 => 1_500_000,
+			#[cfg(any(target_os = "android", target_os = "linux"))]
+			BaudRate::B2000000 => 2_000_000,
+			#[cfg(any(
+				target_os = "android",
+				all(target_os = "linux", not(target_arch = "sparc64"))
+			))]
+			BaudRate::B2500000 => 2_500_000,
+			#[cfg(any(
+				target_os = "android",
+				all(target_os = "linux", not(target_arch = "sparc64"))
+			))]
+			BaudRate::B3000000 => 3_000_000,
+			#[cfg(any(
+				target_os = "android",
+				all(target_os = "linux", not(target_arch = "sparc64"))
+			))]
+			BaudRate::B3500000 => 3_500_000,
+			#[cfg(any(
+				target_os = "android",
+				all(target_os = "linux", not(target_arch = "sparc64"))
+			))]
+			BaudRate::B4000000 => 4_000_000,
+			// rates nix doesn’t know of yet; without the rate, there’s no padding
+			_ => return None,
+		})
+	}
+
+	pub(crate) fn columns() -> Option<u16> {
+		window_size().map(|size| size.ws_col).filter(|&c| c > 0)
+	}
+
+	pub(crate) fn lines() -> Option<u16> {
+		window_size().map(|size| size.ws_row).filter(|&l| l > 0)
+	}
+
+	fn window_size() -> Option<winsize> {
+		with_tty(|fd| {
+			let mut size: winsize = unsafe { std::mem::zeroed() };
+			if unsafe { ioctl(fd.as_raw_fd(), TIOCGWINSZ, &mut size) } == -1 {
+				Ok(None)
+			} else {
+				Ok(Some(size))
+			}
+		})
+		.ok()
+		.flatten()
+	}
+
+	pub(crate) fn read_termios() -> Result<Termios, Error> {
+		with_tty(|fd| Ok(tcgetattr(fd)?))
+	}
+
+	pub(crate) fn write_termios(
+		options: &Options,
+		default_when: TermiosWhen,
+		default_flush: bool,
+		f: impl Fn(&mut Termios),
+	) -> Result<(), Error> {
+		let when = match options.termios_when.unwrap_or(default_when) {
+			TermiosWhen::Now => SetArg::TCSANOW,
+			TermiosWhen::Drain => SetArg::TCSADRAIN,
+			TermiosWhen::Flush => SetArg::TCSAFLUSH,
+		};
+
+		with_tty(|fd| {
+			foreground(fd, options.background, || {
+				let mut t = tcgetattr(fd)?;
+				reset_termios(&mut t);
+				f(&mut t);
+
+				if options.flush_input.unwrap_or(default_flush) {
+					tcflush(fd, FlushArg::TCIFLUSH)?;
+				}
+
+				tcsetattr(fd, when, &t)?;
+				Ok(())
+			})
+		})
+	}
+
+	// Printing to a terminal from the background only raises SIGTTOU when TOSTOP is set.
+	pub(crate) fn foreground_output(
+		options: &Options,
+		f: impl FnOnce() -> Result<(), Error>,
+	) -> Result<(), Error> {
+		let stdout = stdout();
+		let fd = stdout.as_fd();
+		let tostop = isatty(fd.as_raw_fd()).unwrap_or(false)
+			&& tcgetattr(fd).is_ok_and(|t| t.local_flags.contains(LocalFlags::TOSTOP));
+		if !tostop {
+			return f();
 		}
 
-		Ok(())
+		foreground(fd, options.background, f)
+	}
+
+	fn foreground(
+		fd: BorrowedFd,
+		background: Background,
+		f: impl FnOnce() -> Result<(), Error>,
+	) -> Result<(), Error> {
+		// if this fails, the terminal isn't our controlling terminal, and job control doesn't apply
+		let foreground = tcgetpgrp(fd).ok().map(Pid::as_raw);
+		background.run(getpgrp().as_raw(), foreground, f)
+	}
+
+	// Blocks SIGTTOU for the current thread until dropped, then restores the previous mask.
+	pub(crate) struct BlockSigttou(SigSet);
+
+	impl BlockSigttou {
+		pub(crate) fn new() -> Result<Self, Error> {
+			let mut set = SigSet::empty();
+			set.add(Signal::SIGTTOU);
+			Ok(Self(set.thread_swap_mask(SigmaskHow::SIG_BLOCK)?))
+		}
+	}
+
+	impl Drop for BlockSigttou {
+		fn drop(&mut self) {
+			self.0.thread_set_mask().ok();
+		}
 	}
 }
 
@@ -1115,15 +1675,34 @@ mod win {
 #[cfg(not(unix))]
 #[allow(clippy::unnecessary_wraps)]
 mod unix {
-	use super::Error;
+	use super::{Error, Options};
 
-	pub(crate) fn vt_cooked() -> Result<(), Error> {
+	pub(crate) fn vt_cooked(_options: &Options) -> Result<(), Error> {
 		Ok(())
 	}
 
-	pub(crate) fn vt_well_done() -> Result<(), Error> {
+	pub(crate) fn vt_well_done(_options: &Options) -> Result<(), Error> {
 		Ok(())
 	}
+
+	pub(crate) fn foreground_output(
+		_options: &Options,
+		f: impl FnOnce() -> Result<(), Error>,
+	) -> Result<(), Error> {
+		f()
+	}
+
+	pub(crate) fn baud_rate() -> Option<u32> {
+		None
+	}
+
+	pub(crate) fn columns() -> Option<u16> {
+		None
+	}
+
+	pub(crate) fn lines() -> Option<u16> {
+		None
+	}
 }
 
 #[cfg(not(windows))]
@@ -1154,67 +1733,3 @@ mod win {
 		false
 	}
 }
-
-#[derive(Eq, PartialEq, Clone, Debug)]
-struct ResetScrollback<'a>(Cow<'a, [u8]>);
-
-impl<'a> Capability<'a> for ResetScrollback<'a> {
-	#[inline]
-	fn name() -> &'static str {
-		"E3"
-	}
-
-	#[inline]
-	fn from(value: Option<&'a Value>) -> Option<Self> {
-		if let Some(Value::String(value)) = value {
-			Some(Self(Cow::Borrowed(value)))
-		} else {
-			None
-		}
-	}
-
-	#[inline]
-	fn into(self) -> Option<Value> {
-		Some(Value::String(match self.0 {
-			Cow::Borrowed(value) => value.into(),
-
-			Cow::Owned(value) => value,
-		}))
-	}
-}
-
-impl<'a, T: AsRef<&'a [u8]>> From<T> for ResetScrollback<'a> {
-	#[inline]
-	fn from(value: T) -> Self {
-		Self(Cow::Borrowed(value.as_ref()))
-	}
-}
-
-impl<'a> AsRef<[u8]> for ResetScrollback<'a> {
-	#[inline]
-	fn as_ref(&self) -> &[u8] {
-		&self.0
-	}
-}
-
-impl<'a> ResetScrollback<'a> {
-	#[inline]
-	fn expand(&self) -> Expansion<Self> {
-		#[allow(dead_code)]
-		struct ExpansionHere<'a, T: 'a + AsRef<[u8]>> {
-			string: &'a T,
-			params: [Parameter; 9],
-			context: Option<&'a mut Context>,
-		}
-
-		let here = ExpansionHere {
-			string: self,
-			params: Default::default(),
-			context: None,
-		};
-
-		// UNSAFE >:( this is iffy af but also the only way to create an Expansion
-		// such that we can add the E3 capability.
-		unsafe { std::mem::transmute(here) }
-	}
-}
@@ -0,0 +1,335 @@
+use std::{path::PathBuf, time::Duration};
+
+use terminfo::Database;
+
+use crate::Overlay;
+#[cfg(unix)]
+use crate::{unix, Error};
+
+/// Options which adjust how a [`ClearScreen`][crate::ClearScreen] action is performed.
+///
+/// Most variants don’t use any of these, and [`clear()`][crate::ClearScreen::clear()] and
+/// [`clear_to()`][crate::ClearScreen::clear_to()] use the defaults. To change them, build an
+/// `Options` and pass it to [`clear_with()`][crate::ClearScreen::clear_with()] or
+/// [`clear_to_with()`][crate::ClearScreen::clear_to_with()]:
+///
+/// ```no_run
+/// # use clearscreen::{ClearScreen, Options, TermiosWhen};
+/// let options = Options::default().termios_when(TermiosWhen::Now).flush_input(false);
+/// ClearScreen::VtWellDone.clear_with(&options)?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+	pub(crate) termios_when: Option<TermiosWhen>,
+	pub(crate) flush_input: Option<bool>,
+	pub(crate) background: Background,
+	pub(crate) term: Option<String>,
+	pub(crate) database: Option<Database>,
+	pub(crate) padding: Padding,
+	pub(crate) baud_rate: Option<u32>,
+	pub(crate) overlay: Overlay,
+	pub(crate) inherit_stdout: bool,
+	pub(crate) command_timeout: Option<Duration>,
+	pub(crate) infocmp: bool,
+	pub(crate) command_lookup: CommandLookup,
+	pub(crate) command_paths: Vec<(String, PathBuf)>,
+}
+
+impl Options {
+	/// Sets when termios changes take effect.
+	///
+	/// By default, termios changes are applied immediately. See [`flush_input()`] for discarding
+	/// pending input, which [`VtWellDone`][crate::ClearScreen::VtWellDone] does by default.
+	///
+	/// [`TermiosWhen::Drain`] and [`TermiosWhen::Flush`] wait for pending output to be written,
+	/// which never happens on a terminal whose output was stopped with XOFF (Ctrl-S), as a crashed
+	/// program may leave it: the action then hangs until output is resumed.
+	///
+	/// [`flush_input()`]: Options::flush_input()
+	///
+	/// This has no effect on non-Unix targets.
+	pub fn termios_when(mut self, when: TermiosWhen) -> Self {
+		self.termios_when = Some(when);
+		self
+	}
+
+	/// Sets whether the terminal’s input queue is flushed before termios changes are applied.
+	///
+	/// This discards input that has been received but not read, like [`TermiosWhen::Flush`] does,
+	/// but also works with the other [`TermiosWhen`] modes. By default, this is done only for
+	/// [`VtWellDone`][crate::ClearScreen::VtWellDone], as it’s mostly used to recover from a
+	/// crashed program which may have left garbage like mouse reports or terminal query responses
+	/// in the input queue.
+	///
+	/// This has no effect on non-Unix targets.
+	pub fn flush_input(mut self, flush: bool) -> Self {
+		self.flush_input = Some(flush);
+		self
+	}
+
+	/// Sets what to do when the process is not in the terminal’s foreground process group.
+	///
+	/// This applies to termios changes, and to printing to stdout from [`clear_with()`] when the
+	/// terminal has `TOSTOP` set. In both cases the kernel would otherwise send `SIGTTOU` to the
+	/// process group, which stops the entire job. See [`Background`] for the options; the default
+	/// is [`Background::Fail`].
+	///
+	/// This has no effect on non-Unix targets.
+	///
+	/// [`clear_with()`]: crate::ClearScreen::clear_with()
+	pub fn background(mut self, background: Background) -> Self {
+		self.background = background;
+		self
+	}
+
+	/// Sets the terminal to look up in the terminfo database, instead of the `TERM` environment
+	/// variable.
+	///
+	/// This is for producing output for a different terminal than the one the process runs under.
+	/// The entry is looked up in the same places as usual. It only applies to the terminfo variants,
+	/// and to [`TputClear`][crate::ClearScreen::TputClear] and
+	/// [`TputReset`][crate::ClearScreen::TputReset], which pass it to `tput`.
+	///
+	/// ```no_run
+	/// # use clearscreen::{ClearScreen, Options};
+	/// let options = Options::default().term("xterm-kitty");
+	/// let mut seq = Vec::new();
+	/// ClearScreen::for_term("xterm-kitty").clear_to_with(&mut seq, &options)?;
+	/// # Ok::<(), clearscreen::Error>(())
+	/// ```
+	pub fn term(mut self, term: impl Into<String>) -> Self {
+		self.term = Some(term.into());
+		self
+	}
+
+	/// Sets the terminfo database to use, instead of looking one up.
+	///
+	/// This takes precedence over [`term()`][Options::term()]. It only applies to the terminfo
+	/// variants. See also [`ClearScreen::clear_to_with_db()`][crate::ClearScreen::clear_to_with_db()].
+	pub fn database(mut self, database: Database) -> Self {
+		self.database = Some(database);
+		self
+	}
+
+	/// Sets how delays in terminfo capabilities, as in `$<50>`, are handled.
+	///
+	/// See [`Padding`] for the options; the default is [`Padding::Auto`].
+	pub fn padding(mut self, padding: Padding) -> Self {
+		self.padding = padding;
+		self
+	}
+
+	/// Sets the baud rate used to compute terminfo delays, instead of the terminal’s output speed.
+	///
+	/// If this isn’t set and the speed can’t be read, as on non-Unix targets or when there is no
+	/// terminal, it’s assumed to be zero. That effectively disables delays, except mandatory delays
+	/// on terminals which can’t be padded, where they’re applied by sleeping.
+	pub fn baud_rate(mut self, baud_rate: u32) -> Self {
+		self.baud_rate = Some(baud_rate);
+		self
+	}
+
+	/// Sets capabilities to look up before those of the terminfo database.
+	///
+	/// See [`Overlay`] for details. This only applies to the terminfo variants.
+	pub fn overlay(mut self, overlay: Overlay) -> Self {
+		self.overlay = overlay;
+		self
+	}
+
+	/// Sets whether `tput` and other external commands write directly to stdout.
+	///
+	/// By default, the output of [`TputClear`][crate::ClearScreen::TputClear],
+	/// [`TputReset`][crate::ClearScreen::TputReset], and [`ExternalCommand`][crate::ExternalCommand]
+	/// is captured and written to the writer given, like that of the other variants. With this set,
+	/// the command inherits stdout instead, and the writer is bypassed. This is for the rare
+	/// terminal where `tput` needs to talk to the tty itself.
+	pub fn inherit_stdout(mut self, inherit: bool) -> Self {
+		self.inherit_stdout = inherit;
+		self
+	}
+
+	/// Sets how long to wait for an external command before killing it.
+	///
+	/// This applies to [`TputClear`][crate::ClearScreen::TputClear],
+	/// [`TputReset`][crate::ClearScreen::TputReset], [`Cls`][crate::ClearScreen::Cls], and
+	/// [`ExternalCommand`][crate::ExternalCommand]. When
+	/// the command is killed, [`Error::CommandTimeout`][crate::Error::CommandTimeout] is returned.
+	/// By default, there is no timeout.
+	pub fn command_timeout(mut self, timeout: Duration) -> Self {
+		self.command_timeout = Some(timeout);
+		self
+	}
+
+	/// Sets whether to ask `infocmp` for terminfo entries which can’t be found or read otherwise.
+	///
+	/// `infocmp` is part of ncurses, and can read any database that ncurses was built for, such as
+	/// in formats or locations that this library doesn’t know about. The entry it prints is parsed
+	/// and used in-process, so unlike with [`TputClear`][crate::ClearScreen::TputClear], output
+	/// still goes to the writer given. This is tried before the entries of the `embedded-terminfo`
+	/// feature, and for each fallback entry in turn; see [`TerminfoEntry`][crate::TerminfoEntry].
+	///
+	/// This is off by default, as it runs a command. The [`command_timeout()`] applies to it.
+	///
+	/// [`command_timeout()`]: Options::command_timeout()
+	pub fn infocmp(mut self, infocmp: bool) -> Self {
+		self.infocmp = infocmp;
+		self
+	}
+
+	/// Sets where external commands are looked for.
+	///
+	/// See [`CommandLookup`]. By default, they’re looked up in `PATH` as usual.
+	pub fn command_lookup(mut self, lookup: CommandLookup) -> Self {
+		self.command_lookup = lookup;
+		self
+	}
+
+	/// Sets the path of an external command, such as `tput` or `infocmp`, instead of looking it up.
+	///
+	/// With a restricted [`CommandLookup`], the path must be absolute, or running the command fails
+	/// with [`Error::CommandRejected`][crate::Error::CommandRejected].
+	pub fn command_path(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+		let name = name.into();
+		self.command_paths.retain(|(n, _)| *n != name);
+		self.command_paths.push((name, path.into()));
+		self
+	}
+}
+
+/// What to do when acting on a terminal from a background process group.
+///
+/// A process is in the background when its process group is not the terminal’s foreground process
+/// group (as determined by comparing [`tcgetpgrp`] with [`getpgrp`]), for example when it was
+/// started from a shell with `&`, or suspended and then resumed with `bg`.
+///
+/// [`tcgetpgrp`]: https://pubs.opengroup.org/onlinepubs/9699919799/functions/tcgetpgrp.html
+/// [`getpgrp`]: https://pubs.opengroup.org/onlinepubs/9699919799/functions/getpgrp.html
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Background {
+	/// Don’t do anything, and return successfully.
+	Skip,
+
+	/// Block the `SIGTTOU` signal for the duration of the action, and proceed.
+	///
+	/// This makes the action happen even though the process is in the background, which may
+	/// interfere with whatever the foreground process is doing with the terminal.
+	BlockSigttou,
+
+	/// Return [`Error::NotForeground`][crate::Error::NotForeground].
+	///
+	/// This is the default. Without the check, the process would instead be stopped by `SIGTTOU`
+	/// until brought back to the foreground, as it was in earlier versions.
+	#[default]
+	Fail,
+}
+
+impl Background {
+	/// Runs an action on a terminal as set, given the process group of this process and the
+	/// terminal’s foreground process group.
+	///
+	/// The foreground process group is `None` when it can’t be determined, as when the terminal
+	/// isn’t this process’s controlling terminal: job control doesn’t apply then, and the action is
+	/// run.
+	#[cfg(unix)]
+	pub(crate) fn run(
+		self,
+		pgrp: i32,
+		foreground_pgrp: Option<i32>,
+		f: impl FnOnce() -> Result<(), Error>,
+	) -> Result<(), Error> {
+		if foreground_pgrp.map_or(true, |foreground| foreground == pgrp) {
+			return f();
+		}
+
+		match self {
+			Self::Skip => Ok(()),
+			Self::Fail => Err(Error::NotForeground),
+			Self::BlockSigttou => {
+				let _block = unix::BlockSigttou::new()?;
+				f()
+			}
+		}
+	}
+}
+
+/// Where external commands are looked for.
+///
+/// This applies to [`TputClear`][crate::ClearScreen::TputClear],
+/// [`TputReset`][crate::ClearScreen::TputReset], [`Cls`][crate::ClearScreen::Cls],
+/// [`ExternalCommand`][crate::ExternalCommand], and `infocmp` with [`Options::infocmp()`]. Commands
+/// given as an absolute path, or with [`Options::command_path()`], are run from that path.
+///
+/// The restricted lookups are meant for programs which run as root or near setuid boundaries,
+/// where a `PATH` with relative or world-writable directories could make them run something else.
+/// With these, commands given as relative paths are refused. A command which is refused or not
+/// found returns [`Error::CommandRejected`][crate::Error::CommandRejected].
+///
+/// Whatever the lookup, [`Cls`][crate::ClearScreen::Cls] is refused on platforms other than
+/// Windows, where a `cmd.exe` executable would be something else entirely.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CommandLookup {
+	/// Look up commands in `PATH`, as the platform does.
+	#[default]
+	Path,
+
+	/// Look up commands in the directories of `PATH` which can be trusted.
+	///
+	/// These are absolute, and on Unix, neither they nor the command found in them are
+	/// world-writable. Other directories are skipped.
+	TrustedPath,
+
+	/// Look up commands only in these directories, which must be absolute.
+	Dirs(Vec<PathBuf>),
+}
+
+/// How delays in terminfo capabilities are handled.
+///
+/// Capabilities can contain delays such as `$<50>`, which were needed for hardware terminals to
+/// have time to carry out a slow operation like clearing the screen, before they received more.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+	/// Apply delays as ncurses does.
+	///
+	/// Delays marked as mandatory (`$<5/>`) are always applied. Others are only applied when the
+	/// terminal doesn’t use XON/XOFF flow control (`xon`), and the baud rate is at least the padding
+	/// baud rate (`pb`), which is never the case for terminals which don’t define it.
+	///
+	/// A delay is applied by writing as many pad characters (`pad`, or NUL) as can be sent in that
+	/// time at the baud rate, or if the terminal can’t be padded (`npc`), by sleeping.
+	#[default]
+	Auto,
+
+	/// Apply all delays, regardless of flow control and the padding baud rate.
+	///
+	/// This is what `tput` does, as it runs outside of a curses application.
+	Always,
+
+	/// Remove delays without applying them.
+	///
+	/// This is best for virtual terminals, which are fast enough not to need any.
+	Off,
+}
+
+/// When termios changes take effect.
+///
+/// These correspond to the `optional_actions` argument of [`tcsetattr`].
+///
+/// [`tcsetattr`]: https://pubs.opengroup.org/onlinepubs/9699919799/functions/tcsetattr.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TermiosWhen {
+	/// The change occurs immediately (`TCSANOW`).
+	Now,
+
+	/// The change occurs after all output has been transmitted (`TCSADRAIN`).
+	///
+	/// This waits until output is resumed if it was stopped with XOFF.
+	Drain,
+
+	/// The change occurs after all output has been transmitted, and all input that has been
+	/// received but not read is discarded before the change is made (`TCSAFLUSH`).
+	///
+	/// This waits until output is resumed if it was stopped with XOFF.
+	Flush,
+}
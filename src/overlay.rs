@@ -0,0 +1,162 @@
+use std::{fs, path::Path, str::FromStr};
+
+use terminfo::{names::ALIASES, Value};
+
+use super::{info::source, Error};
+
+/// Capabilities to add to or override in terminfo entries, by terminal name.
+///
+/// Terminfo entries are sometimes wrong about what a terminal supports: the `xterm` entries shipped
+/// with macOS lack `E3`, for example, though most terminals using them do clear the scrollback with
+/// it. An overlay fixes up entries like these without having to install a corrected entry. Pass it
+/// to the terminfo variants with [`Options::overlay()`][crate::Options::overlay()], and they will
+/// look up capabilities in the overlay before the terminfo database.
+///
+/// Each rule applies to the terminals whose names match a pattern, in which `*` matches any run of
+/// characters and `?` any single character, and alternatives can be given separated by `|`. Rules
+/// added later take precedence over earlier ones. A rule matches the name of the terminal, as given
+/// with [`Options::term()`][crate::Options::term()] or found in `TERM`, not the name of a fallback
+/// entry which may be used in its place. When a database is given directly instead, it matches the
+/// name of the database.
+///
+/// ```no_run
+/// # use clearscreen::{ClearScreen, Options, Overlay};
+/// let overlay = Overlay::new()
+///     .set("xterm*", "E3", "\x1b[3J")
+///     .cancel("screen*|tmux*", "E3");
+/// ClearScreen::Terminfo.clear_with(&Options::default().overlay(overlay))?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+///
+/// Overlays can also be read from text, one rule per line, with the capabilities in the same
+/// syntax as terminfo source files: `name=string` with the usual escapes like `\E` and `^G`,
+/// `name#number`, a bare `name` for booleans, and `name@` to cancel a capability, separated by
+/// commas. Empty lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # the macOS entries lack E3
+/// xterm*: E3=\E[3J
+/// foot|foot-*: clear=\E[H\E[2J, E3=\E[3J
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overlay {
+	rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Rule {
+	pattern: String,
+	name: String,
+	value: Option<Value>,
+}
+
+impl Overlay {
+	/// An empty overlay.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads an overlay from a file.
+	///
+	/// See above for the format.
+	pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+		fs::read_to_string(path)?.parse()
+	}
+
+	/// Adds or overrides a capability for terminals matching the pattern.
+	///
+	/// The name can be the short or long name of the capability. Strings are taken as-is, without
+	/// interpreting terminfo escapes.
+	pub fn set(mut self, pattern: &str, name: &str, value: impl Into<Value>) -> Self {
+		self.push(pattern, name, Some(value.into()));
+		self
+	}
+
+	/// Removes a capability for terminals matching the pattern.
+	pub fn cancel(mut self, pattern: &str, name: &str) -> Self {
+		self.push(pattern, name, None);
+		self
+	}
+
+	/// Adds the rules of another overlay, which take precedence over those of this one.
+	pub fn extend(mut self, other: Self) -> Self {
+		self.rules.extend(other.rules);
+		self
+	}
+
+	pub(crate) fn is_empty(&self) -> bool {
+		self.rules.is_empty()
+	}
+
+	fn push(&mut self, pattern: &str, name: &str, value: Option<Value>) {
+		self.rules.push(Rule {
+			pattern: pattern.into(),
+			name: ALIASES.get(name).copied().unwrap_or(name).into(),
+			value,
+		});
+	}
+
+	/// Looks up a capability for a terminal.
+	///
+	/// Returns `None` if the overlay doesn’t have it, or `Some(None)` if it cancels it.
+	pub(crate) fn get(&self, term: &str, name: &str) -> Option<Option<&Value>> {
+		let name = ALIASES.get(name).copied().unwrap_or(name);
+		self.rules
+			.iter()
+			.rev()
+			.find(|rule| rule.name == name && matches(&rule.pattern, term))
+			.map(|rule| rule.value.as_ref())
+	}
+}
+
+impl FromStr for Overlay {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut overlay = Self::new();
+		for (i, line) in s.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let err = |reason| Error::OverlayFormat {
+				line: i + 1,
+				reason,
+			};
+			let (pattern, caps) = line.split_once(':').ok_or(err("missing `:`"))?;
+			let pattern = pattern.trim();
+			if pattern.is_empty() {
+				return Err(err("missing terminal pattern"));
+			}
+
+			for cap in source::split(caps) {
+				let cap = cap.trim();
+				if cap.is_empty() {
+					continue;
+				}
+
+				let (name, value) = source::capability(cap).map_err(err)?;
+				overlay.push(pattern, name, value);
+			}
+		}
+
+		Ok(overlay)
+	}
+}
+
+/// Whether a terminal name matches a pattern, with `*` and `?` wildcards and `|` alternatives.
+fn matches(pattern: &str, term: &str) -> bool {
+	pattern
+		.split('|')
+		.any(|alt| glob(alt.trim().as_bytes(), term.as_bytes()))
+}
+
+fn glob(pattern: &[u8], name: &[u8]) -> bool {
+	match pattern.split_first() {
+		None => name.is_empty(),
+		Some((b'*', rest)) => (0..=name.len()).any(|i| glob(rest, &name[i..])),
+		Some((b'?', rest)) => !name.is_empty() && glob(rest, &name[1..]),
+		Some((c, rest)) => name.first() == Some(c) && glob(rest, &name[1..]),
+	}
+}
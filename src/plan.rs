@@ -0,0 +1,335 @@
+use std::{
+	ffi::OsString,
+	fmt,
+	io::{self, Write},
+	thread::sleep,
+	time::Duration,
+};
+
+#[cfg(unix)]
+use super::TermiosProfile;
+use super::{command, info, unix, win, ClearScreen, Error, Options};
+
+/// What a clearing action will do, step by step, without doing it.
+///
+/// This is made by [`ClearScreen::plan()`], and lists the bytes which would be written, the termios
+/// changes and Windows Console calls which would be made, and the commands which would be run. It
+/// can be shown, for example for a `--dry-run` flag, with its [`Display`][fmt::Display]
+/// implementation, which puts each step on its own numbered line. It can be executed later, which
+/// does the same as the action would have.
+///
+/// Most of what the steps need is worked out when planning: the terminfo entry is read then, and
+/// with a restricted [`CommandLookup`][crate::CommandLookup], commands are looked up. With the
+/// default lookup, commands are left for the platform to find when they’re run, so a missing
+/// command is only reported when the plan is executed. Delays in terminfo capabilities which would
+/// be slept rather than padded are [steps][Step::Sleep] of the plan, so planning never waits.
+///
+/// ```no_run
+/// # use clearscreen::ClearScreen;
+/// let plan = ClearScreen::default().plan()?;
+/// eprintln!("{plan}");
+/// plan.execute()?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Plan {
+	steps: Vec<Step>,
+}
+
+impl Plan {
+	/// The steps, in order.
+	pub fn steps(&self) -> &[Step] {
+		&self.steps
+	}
+
+	/// Whether there is nothing to do.
+	pub fn is_empty(&self) -> bool {
+		self.steps.is_empty()
+	}
+
+	/// Adds a step, merging consecutive writes.
+	pub(crate) fn push(&mut self, step: Step) {
+		match (self.steps.last_mut(), step) {
+			(_, Step::Write(bytes)) if bytes.is_empty() => {}
+			(Some(Step::Write(last)), Step::Write(bytes)) => last.extend(bytes),
+			(_, step) => self.steps.push(step),
+		}
+	}
+
+	/// Records what an action writes, and the delays it waits for, as steps.
+	pub(crate) fn record(&mut self) -> Recorder<'_> {
+		Recorder(self)
+	}
+
+	/// Executes the plan, printing to stdout.
+	pub fn execute(&self) -> Result<(), Error> {
+		self.execute_with(&Options::default())
+	}
+
+	/// Executes the plan with the given [`Options`], printing to stdout.
+	///
+	/// Only the options which apply at that point are used, such as
+	/// [`background()`][Options::background()] and
+	/// [`command_timeout()`][Options::command_timeout()].
+	pub fn execute_with(&self, options: &Options) -> Result<(), Error> {
+		unix::foreground_output(options, || {
+			let mut stdout = io::stdout();
+			self.execute_to_with(&mut stdout, options)
+		})
+	}
+
+	/// Executes the plan, printing to a given writer.
+	pub fn execute_to(&self, w: &mut impl Write) -> Result<(), Error> {
+		self.execute_to_with(w, &Options::default())
+	}
+
+	/// Executes the plan with the given [`Options`], printing to a given writer.
+	pub fn execute_to_with(&self, w: &mut impl Write, options: &Options) -> Result<(), Error> {
+		for step in &self.steps {
+			match step {
+				Step::Write(bytes) => {
+					w.write_all(bytes)?;
+					w.flush()?;
+				}
+				Step::Sleep(duration) => {
+					w.flush()?;
+					sleep(*duration);
+				}
+				Step::Run {
+					argv,
+					term,
+					capture,
+				} => command::execute(argv, term.as_deref(), *capture, options, w)?,
+				#[cfg(unix)]
+				Step::Termios(profile) => profile.apply_with(options)?,
+				Step::Console(call) => call.execute()?,
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Shows the steps on numbered lines, or that there is nothing to do.
+impl fmt::Display for Plan {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.steps.is_empty() {
+			return writeln!(f, "nothing to do");
+		}
+
+		for (n, step) in self.steps.iter().enumerate() {
+			writeln!(f, "{}. {step}", n + 1)?;
+		}
+		Ok(())
+	}
+}
+
+/// A step of a [`Plan`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Step {
+	/// Write these bytes.
+	Write(Vec<u8>),
+
+	/// Wait this long, after the bytes written before have been flushed.
+	///
+	/// This is a delay in a terminfo capability, for a terminal which can’t be padded (`npc`).
+	Sleep(Duration),
+
+	/// Run this command.
+	Run {
+		/// The program, as it will be run, and its arguments.
+		argv: Vec<OsString>,
+
+		/// The terminal passed in `TERM`, if any.
+		term: Option<String>,
+
+		/// Whether its output is captured and written to the writer, rather than going to stdout.
+		capture: bool,
+	},
+
+	/// Write this profile to the terminal’s termios.
+	///
+	/// When it takes effect and whether input is flushed are as resolved with the [`Options`] given
+	/// when planning.
+	#[cfg(unix)]
+	Termios(TermiosProfile),
+
+	/// Call the Windows Console API.
+	Console(ConsoleCall),
+}
+
+impl fmt::Display for Step {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Write(bytes) => write!(f, "write \"{}\"", Escaped(bytes)),
+			Self::Sleep(duration) => write!(f, "sleep {duration:?}"),
+			Self::Run {
+				argv,
+				term,
+				capture,
+			} => {
+				write!(f, "run")?;
+				if let Some(term) = term {
+					write!(f, " TERM={term}")?;
+				}
+				for arg in argv {
+					write!(f, " {}", arg.to_string_lossy())?;
+				}
+				if *capture {
+					write!(f, ", writing its output")
+				} else {
+					write!(f, ", with its output to stdout")
+				}
+			}
+			#[cfg(unix)]
+			Self::Termios(profile) => write!(f, "set termios: {profile}"),
+			Self::Console(call) => write!(f, "call {call}"),
+		}
+	}
+}
+
+/// A call to the Windows Console API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConsoleCall {
+	/// Enable VT processing, as [`WindowsVt`][ClearScreen::WindowsVt] does.
+	EnableVt,
+
+	/// Scroll and fill the buffer, as [`WindowsConsoleClear`][ClearScreen::WindowsConsoleClear]
+	/// does.
+	#[cfg(feature = "windows-console")]
+	Clear,
+
+	/// Fill the buffer, as [`WindowsConsoleBlank`][ClearScreen::WindowsConsoleBlank] does.
+	#[cfg(feature = "windows-console")]
+	Blank,
+
+	/// Disable raw mode, as [`WindowsCooked`][ClearScreen::WindowsCooked] does.
+	Cooked,
+}
+
+impl ConsoleCall {
+	fn execute(self) -> Result<(), Error> {
+		match self {
+			Self::EnableVt => win::vt(),
+			#[cfg(feature = "windows-console")]
+			Self::Clear => win::clear(),
+			#[cfg(feature = "windows-console")]
+			Self::Blank => win::blank(),
+			Self::Cooked => win::cooked(),
+		}
+	}
+}
+
+impl fmt::Display for ConsoleCall {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::EnableVt => "SetConsoleMode to enable VT processing",
+			#[cfg(feature = "windows-console")]
+			Self::Clear => "ScrollConsoleScreenBuffer and SetConsoleCursorPosition to clear the screen",
+			#[cfg(feature = "windows-console")]
+			Self::Blank => "FillConsoleOutputCharacter and FillConsoleOutputAttribute to blank the screen",
+			Self::Cooked => "SetConsoleMode to enable cooked mode",
+		})
+	}
+}
+
+/// Records writes and delays into a [`Plan`].
+pub(crate) struct Recorder<'a>(&'a mut Plan);
+
+impl Write for Recorder<'_> {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.push(Step::Write(buf.to_vec()));
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl info::Output for Recorder<'_> {
+	fn pause(&mut self, duration: Duration) -> io::Result<()> {
+		self.0.push(Step::Sleep(duration));
+		Ok(())
+	}
+}
+
+/// Shows bytes with escapes, and `ESC` as `\e`.
+struct Escaped<'a>(&'a [u8]);
+
+impl fmt::Display for Escaped<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for &b in self.0 {
+			if b == 0x1b {
+				write!(f, "\\e")?;
+			} else {
+				write!(f, "{}", b.escape_ascii())?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl ClearScreen {
+	/// Plans the clearing action, without doing anything.
+	///
+	/// See [`Plan`].
+	pub fn plan(self) -> Result<Plan, Error> {
+		self.plan_with(&Options::default())
+	}
+
+	/// Plans the clearing action with the given [`Options`], without doing anything.
+	///
+	/// Errors which the action would return before doing anything, like a missing terminfo entry
+	/// or capability, or a command refused by [`Options::command_lookup()`], are returned here. A
+	/// command which isn’t found with the default lookup is only reported when the plan is
+	/// executed.
+	pub fn plan_with(self, options: &Options) -> Result<Plan, Error> {
+		let mut plan = Plan::default();
+		match self {
+			Self::TputClear => plan.push(command::tput_step(&["clear"], options)?),
+			Self::TputReset => plan.push(command::tput_step(&["reset"], options)?),
+			Self::Cls => plan.push(command::cls_step(options)?),
+			Self::WindowsVt => console(&mut plan, ConsoleCall::EnableVt),
+			Self::WindowsVtClear => {
+				console(&mut plan, ConsoleCall::EnableVt);
+				Self::XtermClear.clear_to_with(&mut plan.record(), options)?;
+			}
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleClear => console(&mut plan, ConsoleCall::Clear),
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleBlank => console(&mut plan, ConsoleCall::Blank),
+			Self::WindowsCooked => console(&mut plan, ConsoleCall::Cooked),
+			#[cfg(unix)]
+			Self::VtCooked => plan.push(Step::Termios(TermiosProfile::cooked().resolve(options))),
+			#[cfg(unix)]
+			Self::VtWellDone => {
+				plan.push(Step::Termios(TermiosProfile::well_done().resolve(options)));
+			}
+			#[cfg(not(unix))]
+			Self::VtCooked | Self::VtWellDone => {}
+			Self::Actions(actions, backend) => actions.plan(backend, options, &mut plan)?,
+			Self::Terminfo
+			| Self::TerminfoScreen
+			| Self::TerminfoScrollback
+			| Self::TerminfoReset
+			| Self::TerminfoRecover
+			| Self::TerminfoCapability(_) => self.terminfo_to_with(&mut plan.record(), options)?,
+			Self::XtermClear
+			| Self::XtermReset
+			| Self::VtRis
+			| Self::VtLeaveAlt
+			| Self::VtRecover => self.clear_to_with(&mut plan.record(), options)?,
+		}
+		Ok(plan)
+	}
+}
+
+/// Adds a console call, which only does anything on Windows.
+pub(crate) fn console(plan: &mut Plan, call: ConsoleCall) {
+	if cfg!(windows) {
+		plan.push(Step::Console(call));
+	}
+}
@@ -0,0 +1,123 @@
+//! The escape sequences written by the fixed-sequence [`ClearScreen`] variants, and their parts.
+//!
+//! These are [`Sequence`]s, which can be written as bytes or embedded in format strings:
+//!
+//! ```
+//! use clearscreen::sequences::{CURSOR_HOME, ERASE_SCREEN};
+//! assert_eq!(format!("{CURSOR_HOME}{ERASE_SCREEN}"), "\x1b[H\x1b[2J");
+//! ```
+//!
+//! See the documentation of each variant for what the sequences do.
+
+use std::fmt;
+
+use super::ClearScreen;
+
+/// A fixed escape sequence.
+///
+/// Its [`Display`][fmt::Display] implementation writes the sequence as is, without escaping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Sequence(&'static str);
+
+impl Sequence {
+	/// The sequence as bytes.
+	pub const fn as_bytes(self) -> &'static [u8] {
+		self.0.as_bytes()
+	}
+
+	/// The sequence as a string.
+	pub const fn as_str(self) -> &'static str {
+		self.0
+	}
+}
+
+impl fmt::Display for Sequence {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.0)
+	}
+}
+
+impl AsRef<[u8]> for Sequence {
+	fn as_ref(&self) -> &[u8] {
+		self.as_bytes()
+	}
+}
+
+impl AsRef<str> for Sequence {
+	fn as_ref(&self) -> &str {
+		self.0
+	}
+}
+
+/// `ESC`, which introduces escape sequences.
+pub const ESC: Sequence = Sequence("\x1b");
+
+/// `CSI` (Control Sequence Introducer), written as `ESC [`.
+pub const CSI: Sequence = Sequence("\x1b[");
+
+/// `ESC c` (Reset to Initial State), as written by [`VtRis`][ClearScreen::VtRis].
+pub const RIS: Sequence = Sequence("\x1bc");
+
+/// `CSI H` (Cursor Position 0,0).
+pub const CURSOR_HOME: Sequence = Sequence("\x1b[H");
+
+/// `CSI 2J` (Erase Screen).
+pub const ERASE_SCREEN: Sequence = Sequence("\x1b[2J");
+
+/// `CSI 3J` (Erase Scrollback), an xterm extension.
+pub const ERASE_SCROLLBACK: Sequence = Sequence("\x1b[3J");
+
+/// `CSI ?1049l` (Leave Alternate Screen), as written by [`VtLeaveAlt`][ClearScreen::VtLeaveAlt].
+pub const LEAVE_ALT_SCREEN: Sequence = Sequence("\x1b[?1049l");
+
+/// What [`XtermClear`][ClearScreen::XtermClear] writes: [`CURSOR_HOME`], [`ERASE_SCREEN`], then
+/// [`ERASE_SCROLLBACK`].
+pub const XTERM_CLEAR: Sequence = Sequence("\x1b[H\x1b[2J\x1b[3J");
+
+/// What [`XtermReset`][ClearScreen::XtermReset] writes: [`RIS`], `CSI !p`, `CSI ?3;4l`, `CSI 4l`,
+/// `ESC >`, then `CSI ?69l`.
+pub const XTERM_RESET: Sequence = Sequence("\x1bc\x1b[!p\x1b[?3;4l\x1b[4l\x1b>\x1b[?69l");
+
+/// What [`VtRecover`][ClearScreen::VtRecover] writes: `CSI r`, `CSI ?6l`, [`LEAVE_ALT_SCREEN`],
+/// `CSI ?25h`, `CSI ?1000;1002;1003;1006l`, `CSI ?2004l`, `CSI ?1004l`, `CSI ?1l`, `ESC >`, then
+/// `CSI m`.
+pub const VT_RECOVER: Sequence = Sequence(concat!(
+	"\x1b[r",
+	"\x1b[?6l",
+	"\x1b[?1049l",
+	"\x1b[?25h",
+	"\x1b[?1000;1002;1003;1006l",
+	"\x1b[?2004l",
+	"\x1b[?1004l",
+	"\x1b[?1l",
+	"\x1b>",
+	"\x1b[m",
+));
+
+impl ClearScreen {
+	/// The fixed escape sequence this variant writes, if it only writes one.
+	///
+	/// This is the case for [`XtermClear`][ClearScreen::XtermClear],
+	/// [`XtermReset`][ClearScreen::XtermReset], [`VtRis`][ClearScreen::VtRis],
+	/// [`VtLeaveAlt`][ClearScreen::VtLeaveAlt], and [`VtRecover`][ClearScreen::VtRecover].
+	pub const fn sequence(self) -> Option<Sequence> {
+		match self {
+			Self::XtermClear => Some(XTERM_CLEAR),
+			Self::XtermReset => Some(XTERM_RESET),
+			Self::VtRis => Some(RIS),
+			Self::VtLeaveAlt => Some(LEAVE_ALT_SCREEN),
+			Self::VtRecover => Some(VT_RECOVER),
+			_ => None,
+		}
+	}
+
+	/// The bytes this variant writes, if they’re a fixed sequence.
+	///
+	/// See [`sequence()`][ClearScreen::sequence()].
+	pub const fn as_bytes(self) -> Option<&'static [u8]> {
+		match self.sequence() {
+			Some(seq) => Some(seq.as_bytes()),
+			None => None,
+		}
+	}
+}
@@ -0,0 +1,313 @@
+use std::{borrow::Cow, env, fmt, io, io::Write, sync::Arc};
+
+use super::{unix, Backend, ClearActions, ClearScreen, Error, ExternalCommand, Options};
+
+/// A way to clear the screen.
+///
+/// This is implemented by [`ClearScreen`] and [`ExternalCommand`], and can be implemented outside
+/// of this library for other methods, such as a terminal’s proprietary sequence. With a
+/// [`Registry`], such strategies can be looked up by name, and selected for the terminals they
+/// handle.
+///
+/// ```
+/// # use std::{borrow::Cow, io::Write};
+/// # use clearscreen::{Capabilities, ClearActions, ClearStrategy, Error, Options, Registry};
+/// #[derive(Debug)]
+/// struct AcmeClear;
+///
+/// impl ClearStrategy for AcmeClear {
+///     fn name(&self) -> Cow<'_, str> {
+///         "AcmeClear".into()
+///     }
+///
+///     fn capabilities(&self) -> Capabilities {
+///         Capabilities::new(ClearActions::ERASE_SCREEN | ClearActions::ERASE_SCROLLBACK)
+///     }
+///
+///     fn handles_term(&self, term: &str) -> bool {
+///         term.starts_with("acme")
+///     }
+///
+///     fn clear_to_with(&self, w: &mut dyn Write, _options: &Options) -> Result<(), Error> {
+///         w.write_all(b"\x1b[=clear-all]")?;
+///         w.flush()?;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut registry = Registry::new();
+/// registry.register(AcmeClear);
+/// assert_eq!(registry.for_term("acme-256color").name(), "AcmeClear");
+/// assert!(registry.get("XtermClear").is_some());
+/// ```
+pub trait ClearStrategy: fmt::Debug + Send + Sync {
+	/// The name of the strategy, by which it’s looked up in a [`Registry`].
+	fn name(&self) -> Cow<'_, str>;
+
+	/// What the strategy does, and what it needs to do it.
+	fn capabilities(&self) -> Capabilities;
+
+	/// Whether the strategy should be selected for the named terminal.
+	///
+	/// This is used by [`Registry::for_term()`] and [`Registry::for_env()`]. The built-in
+	/// strategies return `false`, as they’re selected as [`ClearScreen::for_term()`] does.
+	fn handles_term(&self, term: &str) -> bool {
+		let _ = term;
+		false
+	}
+
+	/// Performs the clearing action with the given [`Options`], printing to a given writer.
+	fn clear_to_with(&self, w: &mut dyn Write, options: &Options) -> Result<(), Error>;
+
+	/// Performs the clearing action, printing to a given writer.
+	fn clear_to(&self, w: &mut dyn Write) -> Result<(), Error> {
+		self.clear_to_with(w, &Options::default())
+	}
+
+	/// Performs the clearing action with the given [`Options`], printing to stdout.
+	fn clear_with(&self, options: &Options) -> Result<(), Error> {
+		unix::foreground_output(options, || self.clear_to_with(&mut io::stdout(), options))
+	}
+
+	/// Performs the clearing action, printing to stdout.
+	fn clear(&self) -> Result<(), Error> {
+		self.clear_with(&Options::default())
+	}
+}
+
+/// What a [`ClearStrategy`] does, and what it needs to do it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Capabilities {
+	/// The actions it performs.
+	pub actions: ClearActions,
+
+	/// Whether it reads the terminal’s terminfo entry.
+	pub terminfo: bool,
+
+	/// Whether it runs an external command.
+	pub command: bool,
+}
+
+impl Capabilities {
+	/// Capabilities which perform the given actions, without terminfo or commands.
+	pub fn new(actions: ClearActions) -> Self {
+		Self {
+			actions,
+			..Self::default()
+		}
+	}
+
+	/// Sets whether the terminal’s terminfo entry is read.
+	pub fn terminfo(mut self, terminfo: bool) -> Self {
+		self.terminfo = terminfo;
+		self
+	}
+
+	/// Sets whether an external command is run.
+	pub fn command(mut self, command: bool) -> Self {
+		self.command = command;
+		self
+	}
+}
+
+impl ClearStrategy for ClearScreen {
+	/// The name of the variant, like `XtermClear`, followed by its parameters if it has any, like
+	/// `TerminfoCapability(sgr0)` or `Actions(HOME_CURSOR | ERASE_SCREEN, Xterm)`.
+	fn name(&self) -> Cow<'_, str> {
+		match self {
+			Self::Terminfo => "Terminfo".into(),
+			Self::TerminfoScreen => "TerminfoScreen".into(),
+			Self::TerminfoScrollback => "TerminfoScrollback".into(),
+			Self::TerminfoReset => "TerminfoReset".into(),
+			Self::TerminfoRecover => "TerminfoRecover".into(),
+			Self::TerminfoCapability(name) => format!("TerminfoCapability({name})").into(),
+			Self::XtermClear => "XtermClear".into(),
+			Self::XtermReset => "XtermReset".into(),
+			Self::TputClear => "TputClear".into(),
+			Self::TputReset => "TputReset".into(),
+			Self::Cls => "Cls".into(),
+			Self::WindowsVt => "WindowsVt".into(),
+			Self::WindowsVtClear => "WindowsVtClear".into(),
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleClear => "WindowsConsoleClear".into(),
+			#[cfg(feature = "windows-console")]
+			Self::WindowsConsoleBlank => "WindowsConsoleBlank".into(),
+			Self::WindowsCooked => "WindowsCooked".into(),
+			Self::VtRis => "VtRis".into(),
+			Self::VtLeaveAlt => "VtLeaveAlt".into(),
+			Self::VtRecover => "VtRecover".into(),
+			Self::VtCooked => "VtCooked".into(),
+			Self::VtWellDone => "VtWellDone".into(),
+			Self::Actions(actions, backend) => {
+				let backend = match backend {
+					Backend::Terminfo => "Terminfo",
+					Backend::Xterm => "Xterm",
+					Backend::Tput => "Tput",
+					Backend::WindowsConsole => "WindowsConsole",
+				};
+				format!("Actions({actions}, {backend})").into()
+			}
+		}
+	}
+
+	fn capabilities(&self) -> Capabilities {
+		const ALL: ClearActions = ClearActions::HOME_CURSOR
+			.union(ClearActions::ERASE_SCREEN)
+			.union(ClearActions::ERASE_SCROLLBACK);
+
+		let (actions, backend) = match self.actions() {
+			Some((actions, backend)) => (actions, Some(backend)),
+			None => match self {
+				Self::Cls | Self::WindowsVtClear => (ALL, None),
+				#[cfg(feature = "windows-console")]
+				Self::WindowsConsoleBlank => (ALL, None),
+				Self::VtRis => (ClearActions::FULL_RESET, None),
+				Self::VtCooked => (ClearActions::RESTORE_TERMIOS, None),
+				Self::TerminfoCapability(_) => (ClearActions::empty(), Some(Backend::Terminfo)),
+				_ => (ClearActions::empty(), None),
+			},
+		};
+
+		Capabilities::new(actions)
+			.terminfo(backend == Some(Backend::Terminfo))
+			.command(backend == Some(Backend::Tput) || *self == Self::Cls)
+	}
+
+	fn clear_to_with(&self, mut w: &mut dyn Write, options: &Options) -> Result<(), Error> {
+		ClearScreen::clear_to_with(*self, &mut w, options)
+	}
+}
+
+impl ClearStrategy for ExternalCommand {
+	/// The program, as it was given.
+	fn name(&self) -> Cow<'_, str> {
+		self.get_program().to_string_lossy()
+	}
+
+	/// Nothing is known of what the command does.
+	fn capabilities(&self) -> Capabilities {
+		Capabilities::default().command(true)
+	}
+
+	fn clear_to_with(&self, mut w: &mut dyn Write, options: &Options) -> Result<(), Error> {
+		ExternalCommand::clear_to_with(self, &mut w, options)
+	}
+}
+
+/// A set of [`ClearStrategy`]s, to look them up by name and select them for terminals.
+///
+/// A new registry has the [`ClearScreen`] variants, except those which take parameters. Strategies
+/// registered later take precedence over earlier ones: a strategy with the same name as another
+/// replaces it in lookups, and strategies which [handle][ClearStrategy::handles_term()] a terminal
+/// are selected before the built-in selection is made.
+#[derive(Clone, Debug)]
+pub struct Registry {
+	strategies: Vec<Arc<dyn ClearStrategy>>,
+}
+
+impl Registry {
+	/// A registry with the built-in strategies.
+	pub fn new() -> Self {
+		let mut registry = Self::empty();
+		for &cs in BUILTIN {
+			registry.register(cs);
+		}
+		registry
+	}
+
+	/// A registry without any strategies.
+	///
+	/// Selection still falls back to the built-in strategies, but they can’t be looked up by name.
+	pub fn empty() -> Self {
+		Self {
+			strategies: Vec::new(),
+		}
+	}
+
+	/// Adds a strategy.
+	pub fn register(&mut self, strategy: impl ClearStrategy + 'static) -> &mut Self {
+		self.strategies.push(Arc::new(strategy));
+		self
+	}
+
+	/// Looks up a strategy by name.
+	pub fn get(&self, name: &str) -> Option<Arc<dyn ClearStrategy>> {
+		self.strategies
+			.iter()
+			.rev()
+			.find(|strategy| strategy.name() == name)
+			.cloned()
+	}
+
+	/// The names of the strategies, in the order they were registered, without duplicates.
+	pub fn names(&self) -> Vec<String> {
+		let mut names: Vec<String> = Vec::new();
+		for strategy in &self.strategies {
+			let name = strategy.name();
+			if !names.iter().any(|n| *n == name) {
+				names.push(name.into_owned());
+			}
+		}
+		names
+	}
+
+	/// Selects a strategy for the named terminal.
+	///
+	/// This is the last registered strategy which handles the terminal, or else
+	/// [`ClearScreen::for_term()`].
+	pub fn for_term(&self, term: &str) -> Arc<dyn ClearStrategy> {
+		self.handling(term)
+			.unwrap_or_else(|| Arc::new(ClearScreen::for_term(term)))
+	}
+
+	/// Selects a strategy for the current terminal.
+	///
+	/// This is the last registered strategy which handles the terminal named by `TERM`, or else
+	/// [`ClearScreen::default()`].
+	pub fn for_env(&self) -> Arc<dyn ClearStrategy> {
+		env::var("TERM")
+			.ok()
+			.and_then(|term| self.handling(&term))
+			.unwrap_or_else(|| Arc::new(ClearScreen::default()))
+	}
+
+	fn handling(&self, term: &str) -> Option<Arc<dyn ClearStrategy>> {
+		self.strategies
+			.iter()
+			.rev()
+			.find(|strategy| strategy.handles_term(term))
+			.cloned()
+	}
+}
+
+impl Default for Registry {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+const BUILTIN: &[ClearScreen] = &[
+	ClearScreen::Terminfo,
+	ClearScreen::TerminfoScreen,
+	ClearScreen::TerminfoScrollback,
+	ClearScreen::TerminfoReset,
+	ClearScreen::TerminfoRecover,
+	ClearScreen::XtermClear,
+	ClearScreen::XtermReset,
+	ClearScreen::TputClear,
+	ClearScreen::TputReset,
+	ClearScreen::Cls,
+	ClearScreen::WindowsVt,
+	ClearScreen::WindowsVtClear,
+	#[cfg(feature = "windows-console")]
+	ClearScreen::WindowsConsoleClear,
+	#[cfg(feature = "windows-console")]
+	ClearScreen::WindowsConsoleBlank,
+	ClearScreen::WindowsCooked,
+	ClearScreen::VtRis,
+	ClearScreen::VtLeaveAlt,
+	ClearScreen::VtRecover,
+	ClearScreen::VtCooked,
+	ClearScreen::VtWellDone,
+];
@@ -0,0 +1,402 @@
+use std::{fmt, str::FromStr};
+
+use nix::{
+	libc::{cc_t, tcflag_t, termios},
+	sys::termios::{
+		ControlFlags, InputFlags, LocalFlags, OutputFlags, SpecialCharacterIndices, Termios, NCCS,
+	},
+};
+
+use super::{unix, Error, Options, TermiosWhen};
+
+/// A snapshot of the terminal’s termios configuration.
+///
+/// This is the set of configuration bits that [`VtCooked`][crate::ClearScreen::VtCooked] and
+/// [`VtWellDone`][crate::ClearScreen::VtWellDone] write to, but captured as-is from a terminal, so
+/// it can be restored later. The terminal used is the same as for these variants: stdin if it is a
+/// tty, otherwise the controlling terminal at `/dev/tty`.
+///
+/// It can be serialised to and parsed from the colon-separated hexadecimal format that GNU `stty -g`
+/// uses, via its [`Display`][fmt::Display] and [`FromStr`] implementations. That makes it possible
+/// to save a known-good state in one process and restore it from another, and to interoperate with
+/// `stty` itself:
+///
+/// ```no_run
+/// # use clearscreen::TermiosState;
+/// let saved = TermiosState::read()?.to_string();
+/// std::fs::write("saved-tty", &saved)?;
+///
+/// // ...later, possibly in another process, or with `stty $(cat saved-tty)`:
+/// let state: TermiosState = std::fs::read_to_string("saved-tty")?.parse()?;
+/// state.apply()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// Like `stty -g`, only the mode flags and the control characters are captured. The baud rate is
+/// carried in the control flags on Linux, but not on BSDs and macOS, where it is left unchanged.
+///
+/// Only available on Unix targets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TermiosState {
+	/// Input mode flags (`c_iflag`).
+	pub input_flags: InputFlags,
+
+	/// Output mode flags (`c_oflag`).
+	pub output_flags: OutputFlags,
+
+	/// Control mode flags (`c_cflag`).
+	pub control_flags: ControlFlags,
+
+	/// Local mode flags (`c_lflag`).
+	pub local_flags: LocalFlags,
+
+	/// Control characters (`c_cc`).
+	pub control_chars: [cc_t; NCCS],
+}
+
+impl TermiosState {
+	/// Reads the current termios configuration of the terminal.
+	pub fn read() -> Result<Self, Error> {
+		// go through the libc struct so unknown bits aren’t truncated
+		let t: termios = unix::read_termios()?.into();
+		Ok(Self {
+			input_flags: InputFlags::from_bits_retain(t.c_iflag),
+			output_flags: OutputFlags::from_bits_retain(t.c_oflag),
+			control_flags: ControlFlags::from_bits_retain(t.c_cflag),
+			local_flags: LocalFlags::from_bits_retain(t.c_lflag),
+			control_chars: t.c_cc,
+		})
+	}
+
+	/// Writes this configuration to the terminal.
+	///
+	/// This goes through the same path as [`VtCooked`][crate::ClearScreen::VtCooked] and
+	/// [`VtWellDone`][crate::ClearScreen::VtWellDone], and so has the same caveats.
+	///
+	/// By default, the change is applied immediately and pending input is left alone.
+	pub fn apply(&self) -> Result<(), Error> {
+		self.apply_with(&Options::default())
+	}
+
+	/// Writes this configuration to the terminal, with the given [`Options`].
+	///
+	/// See [`Options::termios_when()`] and [`Options::flush_input()`].
+	pub fn apply_with(&self, options: &Options) -> Result<(), Error> {
+		unix::write_termios(options, TermiosWhen::Now, false, |t| self.write_to(t))
+	}
+
+	fn from_termios(t: &Termios) -> Self {
+		Self {
+			input_flags: t.input_flags,
+			output_flags: t.output_flags,
+			control_flags: t.control_flags,
+			local_flags: t.local_flags,
+			control_chars: t.control_chars,
+		}
+	}
+
+	fn write_to(&self, t: &mut Termios) {
+		t.input_flags = self.input_flags;
+		t.output_flags = self.output_flags;
+		t.control_flags = self.control_flags;
+		t.local_flags = self.local_flags;
+		t.control_chars = self.control_chars;
+	}
+}
+
+/// Formats the state as `stty -g` does.
+impl fmt::Display for TermiosState {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{:x}:{:x}:{:x}:{:x}",
+			self.input_flags.bits(),
+			self.output_flags.bits(),
+			self.control_flags.bits(),
+			self.local_flags.bits()
+		)?;
+
+		for cc in self.control_chars {
+			write!(f, ":{cc:x}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Parses the state from the output of `stty -g`.
+///
+/// As with GNU `stty`, this is strict: there must be exactly four flag fields followed by one field
+/// per control character supported by the platform, and bits which aren’t known to this library are
+/// kept as-is rather than discarded.
+impl FromStr for TermiosState {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let fields: Vec<&str> = s.trim().split(':').collect();
+		if fields.len() != 4 + NCCS {
+			return Err(Error::SttyFormat("wrong number of fields"));
+		}
+
+		let mut control_chars = [0; NCCS];
+		for (cc, field) in control_chars.iter_mut().zip(&fields[4..]) {
+			*cc = cc_t::from_str_radix(field, 16)
+				.map_err(|_| Error::SttyFormat("invalid control character"))?;
+		}
+
+		Ok(Self {
+			input_flags: InputFlags::from_bits_retain(parse_flags(fields[0])?),
+			output_flags: OutputFlags::from_bits_retain(parse_flags(fields[1])?),
+			control_flags: ControlFlags::from_bits_retain(parse_flags(fields[2])?),
+			local_flags: LocalFlags::from_bits_retain(parse_flags(fields[3])?),
+			control_chars,
+		})
+	}
+}
+
+fn parse_flags(field: &str) -> Result<tcflag_t, Error> {
+	tcflag_t::from_str_radix(field, 16).map_err(|_| Error::SttyFormat("invalid flags"))
+}
+
+/// A set of termios configuration bits to write to the terminal.
+///
+/// The [`VtCooked`][crate::ClearScreen::VtCooked] and [`VtWellDone`][crate::ClearScreen::VtWellDone]
+/// variants are predefined profiles, available as [`cooked()`][TermiosProfile::cooked()] and
+/// [`well_done()`][TermiosProfile::well_done()]. These can be used as a base to build variants,
+/// or a profile can be built from scratch with [`new()`][TermiosProfile::new()].
+///
+/// Like these variants, a profile authoritatively writes the entire configuration: all flags which
+/// aren’t in the profile are cleared. Control characters are left as they are, unless set with
+/// [`cc()`][TermiosProfile::cc()].
+///
+/// For example, for a serial console which uses hardware flow control and 8-bit data:
+///
+/// ```no_run
+/// use clearscreen::{ControlFlags, InputFlags, TermiosProfile};
+///
+/// TermiosProfile::well_done()
+///     .with_control(ControlFlags::CRTSCTS)
+///     .without_input(InputFlags::ISTRIP)
+///     .apply()?;
+/// # Ok::<(), clearscreen::Error>(())
+/// ```
+///
+/// Only available on Unix targets.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TermiosProfile {
+	input_flags: InputFlags,
+	output_flags: OutputFlags,
+	control_flags: ControlFlags,
+	local_flags: LocalFlags,
+	control_chars: Vec<(SpecialCharacterIndices, cc_t)>,
+	when: TermiosWhen,
+	flush_input: bool,
+}
+
+impl TermiosProfile {
+	/// An empty profile, with all flags cleared.
+	///
+	/// Applying this as-is is probably not what you want.
+	pub fn new() -> Self {
+		Self {
+			input_flags: InputFlags::empty(),
+			output_flags: OutputFlags::empty(),
+			control_flags: ControlFlags::empty(),
+			local_flags: LocalFlags::empty(),
+			control_chars: Vec::new(),
+			when: TermiosWhen::Now,
+			flush_input: false,
+		}
+	}
+
+	/// The profile used by [`VtCooked`][crate::ClearScreen::VtCooked].
+	pub fn cooked() -> Self {
+		Self::new()
+			.with_input(
+				InputFlags::BRKINT
+					| InputFlags::ICRNL | InputFlags::IGNPAR
+					| InputFlags::ISTRIP | InputFlags::IXON,
+			)
+			.with_output(OutputFlags::OPOST)
+			.with_local(LocalFlags::ICANON | LocalFlags::ISIG)
+	}
+
+	/// The profile used by [`VtWellDone`][crate::ClearScreen::VtWellDone].
+	///
+	/// This also defaults to flushing pending input. Like other profiles, it’s applied immediately
+	/// ([`TermiosWhen::Now`]), so that it doesn’t wait for output which is stopped with XOFF.
+	pub fn well_done() -> Self {
+		let mut input = InputFlags::BRKINT
+			| InputFlags::ICRNL
+			| InputFlags::IGNPAR
+			| InputFlags::IMAXBEL
+			| InputFlags::ISTRIP
+			| InputFlags::IXON;
+
+		#[cfg(any(target_os = "android", target_os = "linux", target_os = "macos"))]
+		{
+			input |= InputFlags::IUTF8;
+		}
+
+		Self::new()
+			.with_input(input)
+			.with_output(OutputFlags::ONLCR | OutputFlags::OPOST)
+			.with_control(ControlFlags::CREAD)
+			.with_local(LocalFlags::ICANON | LocalFlags::ISIG)
+			.flush_input(true)
+	}
+
+	/// Sets input mode flags.
+	pub fn with_input(mut self, flags: InputFlags) -> Self {
+		self.input_flags.insert(flags);
+		self
+	}
+
+	/// Clears input mode flags.
+	pub fn without_input(mut self, flags: InputFlags) -> Self {
+		self.input_flags.remove(flags);
+		self
+	}
+
+	/// Sets output mode flags.
+	pub fn with_output(mut self, flags: OutputFlags) -> Self {
+		self.output_flags.insert(flags);
+		self
+	}
+
+	/// Clears output mode flags.
+	pub fn without_output(mut self, flags: OutputFlags) -> Self {
+		self.output_flags.remove(flags);
+		self
+	}
+
+	/// Sets control mode flags.
+	pub fn with_control(mut self, flags: ControlFlags) -> Self {
+		self.control_flags.insert(flags);
+		self
+	}
+
+	/// Clears control mode flags.
+	pub fn without_control(mut self, flags: ControlFlags) -> Self {
+		self.control_flags.remove(flags);
+		self
+	}
+
+	/// Sets local mode flags.
+	pub fn with_local(mut self, flags: LocalFlags) -> Self {
+		self.local_flags.insert(flags);
+		self
+	}
+
+	/// Clears local mode flags.
+	pub fn without_local(mut self, flags: LocalFlags) -> Self {
+		self.local_flags.remove(flags);
+		self
+	}
+
+	/// Sets a control character.
+	pub fn cc(mut self, index: SpecialCharacterIndices, value: cc_t) -> Self {
+		self.control_chars.retain(|(i, _)| *i != index);
+		self.control_chars.push((index, value));
+		self
+	}
+
+	/// Sets when the profile takes effect, unless overridden by [`Options::termios_when()`].
+	///
+	/// Defaults to [`TermiosWhen::Now`].
+	pub fn when(mut self, when: TermiosWhen) -> Self {
+		self.when = when;
+		self
+	}
+
+	/// Sets whether to flush pending input, unless overridden by [`Options::flush_input()`].
+	///
+	/// Defaults to false.
+	pub fn flush_input(mut self, flush: bool) -> Self {
+		self.flush_input = flush;
+		self
+	}
+
+	/// This profile with when it takes effect and whether input is flushed as overridden by the
+	/// [`Options`], as they would be when it’s applied.
+	pub(crate) fn resolve(self, options: &Options) -> Self {
+		let when = options.termios_when.unwrap_or(self.when);
+		let flush = options.flush_input.unwrap_or(self.flush_input);
+		self.when(when).flush_input(flush)
+	}
+
+	/// Writes this profile to the terminal.
+	pub fn apply(&self) -> Result<(), Error> {
+		self.apply_with(&Options::default())
+	}
+
+	/// Writes this profile to the terminal, with the given [`Options`].
+	pub fn apply_with(&self, options: &Options) -> Result<(), Error> {
+		unix::write_termios(options, self.when, self.flush_input, |t| {
+			self.applied_to(&TermiosState::from_termios(t)).write_to(t);
+		})
+	}
+
+	/// The configuration that applying this profile to a terminal in the given state would write.
+	///
+	/// The flags are those of the profile, and the control characters are those of the state with
+	/// the ones set by [`cc()`][TermiosProfile::cc()] replaced.
+	pub fn applied_to(&self, state: &TermiosState) -> TermiosState {
+		let mut control_chars = state.control_chars;
+		for (index, value) in &self.control_chars {
+			control_chars[*index as usize] = *value;
+		}
+
+		TermiosState {
+			input_flags: self.input_flags,
+			output_flags: self.output_flags,
+			control_flags: self.control_flags,
+			local_flags: self.local_flags,
+			control_chars,
+		}
+	}
+}
+
+/// Shows the flags which are set, then the control characters, when it takes effect, and whether
+/// input is flushed.
+impl fmt::Display for TermiosProfile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fn names<'a>(iter: impl Iterator<Item = (&'a str, impl Sized)>) -> String {
+			let names = iter.map(|(name, _)| name).collect::<Vec<_>>();
+			if names.is_empty() {
+				"-".into()
+			} else {
+				names.join("|")
+			}
+		}
+
+		write!(
+			f,
+			"input {}, output {}, control {}, local {}",
+			names(self.input_flags.iter_names()),
+			names(self.output_flags.iter_names()),
+			names(self.control_flags.iter_names()),
+			names(self.local_flags.iter_names()),
+		)?;
+		for (index, value) in &self.control_chars {
+			write!(f, ", {index:?}={value}")?;
+		}
+
+		let when = match self.when {
+			TermiosWhen::Now => "TCSANOW",
+			TermiosWhen::Drain => "TCSADRAIN",
+			TermiosWhen::Flush => "TCSAFLUSH",
+		};
+		write!(f, " ({when}")?;
+		if self.flush_input {
+			write!(f, ", flushing input")?;
+		}
+		write!(f, ")")
+	}
+}
+
+impl Default for TermiosProfile {
+	fn default() -> Self {
+		Self::new()
+	}
+}
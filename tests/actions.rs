@@ -0,0 +1,178 @@
+use clearscreen::{Backend, ClearActions, ClearScreen, Error, Options};
+
+mod common;
+
+fn perform(actions: ClearActions, backend: Backend, options: &Options) -> Result<Vec<u8>, Error> {
+	let mut out = Vec::new();
+	ClearScreen::Actions(actions, backend).clear_to_with(&mut out, options)?;
+	Ok(out)
+}
+
+fn clear_to(cs: ClearScreen, options: &Options) -> Vec<u8> {
+	let mut out = Vec::new();
+	cs.clear_to_with(&mut out, options).unwrap();
+	out
+}
+
+#[test]
+fn xterm() {
+	let options = Options::default();
+	assert_eq!(
+		perform(
+			ClearActions::ERASE_SCROLLBACK | ClearActions::HOME_CURSOR,
+			Backend::Xterm,
+			&options
+		)
+		.unwrap(),
+		b"\x1b[H\x1b[3J"
+	);
+	assert_eq!(
+		perform(
+			ClearActions::ERASE_SCREEN | ClearActions::LEAVE_ALT_SCREEN,
+			Backend::Xterm,
+			&options
+		)
+		.unwrap(),
+		b"\x1b[?1049l\x1b[2J"
+	);
+}
+
+#[test]
+fn terminfo() {
+	let options = Options::default().database(common::database(&[]));
+	assert_eq!(
+		perform(
+			ClearActions::ERASE_SCREEN | ClearActions::LEAVE_ALT_SCREEN,
+			Backend::Terminfo,
+			&options
+		)
+		.unwrap(),
+		b"\x1b[?1049l\x1b[H\x1b[J"
+	);
+	assert_eq!(
+		perform(ClearActions::HOME_CURSOR, Backend::Terminfo, &options).unwrap(),
+		b"\x1b[H"
+	);
+}
+
+#[test]
+fn leave_alt_screen_once() {
+	let actions = ClearActions::LEAVE_ALT_SCREEN | ClearActions::RESET_MODES;
+
+	let options = Options::default().database(common::database(&[]));
+	assert_eq!(
+		perform(actions, Backend::Terminfo, &options).unwrap(),
+		b"\x1b[?1049l\x1b[m"
+	);
+
+	let out = perform(actions, Backend::Xterm, &Options::default()).unwrap();
+	let out = String::from_utf8(out).unwrap();
+	assert!(out.starts_with("\x1b[?1049l\x1b[r"));
+	assert_eq!(out.matches("\x1b[?1049l").count(), 1);
+}
+
+#[test]
+fn terminfo_scrollback_optional_with_screen() {
+	let options = Options::default().database(common::entry(
+		"fixture",
+		&[("clear", "\x1b[H\x1b[J".into())],
+	));
+
+	assert_eq!(
+		perform(
+			ClearActions::ERASE_SCREEN | ClearActions::ERASE_SCROLLBACK,
+			Backend::Terminfo,
+			&options
+		)
+		.unwrap(),
+		b"\x1b[H\x1b[J"
+	);
+	assert!(matches!(
+		perform(ClearActions::ERASE_SCROLLBACK, Backend::Terminfo, &options),
+		Err(Error::TerminfoCapNotFound {
+			cap: "E3",
+			entry: None,
+			..
+		})
+	));
+}
+
+#[test]
+fn variants_are_equivalent() {
+	let options = Options::default().database(common::database(&[]));
+	for cs in [
+		ClearScreen::Terminfo,
+		ClearScreen::TerminfoScreen,
+		ClearScreen::TerminfoScrollback,
+		ClearScreen::XtermClear,
+		ClearScreen::XtermReset,
+		ClearScreen::VtLeaveAlt,
+		ClearScreen::VtRecover,
+	] {
+		let (actions, backend) = cs.actions().unwrap();
+		assert_eq!(
+			clear_to(ClearScreen::Actions(actions, backend), &options),
+			clear_to(cs, &options),
+			"{cs:?}"
+		);
+	}
+
+	assert_eq!(ClearScreen::VtRis.actions(), None);
+	assert_eq!(ClearScreen::TerminfoCapability("sgr0").actions(), None);
+}
+
+#[test]
+fn unsupported() {
+	let actions = ClearActions::RESET_MODES | ClearActions::ERASE_SCREEN;
+	let err = perform(actions, Backend::Tput, &Options::default()).unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"the tput backend can’t perform RESET_MODES"
+	);
+	match err {
+		Error::UnsupportedActions { actions, backend } => {
+			assert_eq!(actions, ClearActions::RESET_MODES);
+			assert_eq!(backend, Backend::Tput);
+		}
+		other => panic!("{other:?}"),
+	}
+
+	assert!(matches!(
+		perform(
+			ClearActions::HOME_CURSOR,
+			Backend::WindowsConsole,
+			&Options::default()
+		),
+		Err(Error::UnsupportedActions { .. })
+	));
+}
+
+#[test]
+fn display() {
+	assert_eq!(ClearActions::empty().to_string(), "(none)");
+	assert_eq!(
+		(ClearActions::ERASE_SCROLLBACK | ClearActions::HOME_CURSOR).to_string(),
+		"HOME_CURSOR | ERASE_SCROLLBACK"
+	);
+	assert_eq!(
+		format!("{:?}", ClearActions::FULL_RESET),
+		"ClearActions(FULL_RESET)"
+	);
+}
+
+#[test]
+fn operators() {
+	let mut actions = ClearActions::HOME_CURSOR;
+	actions |= ClearActions::ERASE_SCREEN;
+	assert!(actions.contains(ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN));
+	assert!(!actions.contains(ClearActions::ERASE_SCROLLBACK));
+	assert_eq!(
+		actions - ClearActions::HOME_CURSOR,
+		ClearActions::ERASE_SCREEN
+	);
+	assert_eq!(
+		actions & ClearActions::HOME_CURSOR,
+		ClearActions::HOME_CURSOR
+	);
+	assert!((actions - actions).is_empty());
+}
@@ -0,0 +1,108 @@
+#![cfg(unix)]
+
+//! Acting on a terminal from a background process group.
+//!
+//! This needs a terminal with job control, so the test runs this test binary again in a new
+//! session on a pseudo-terminal, which in turn runs it once more in a process group of its own,
+//! in the background of that terminal. The `_child` tests are only run like that.
+
+use std::{
+	env,
+	os::unix::process::CommandExt,
+	process::{Command, Stdio},
+	thread,
+	time::{Duration, Instant},
+};
+
+use clearscreen::{Background, ClearScreen, Error, LocalFlags, Options, TermiosState};
+use nix::{
+	libc::{ioctl, TIOCSCTTY},
+	pty::openpty,
+	unistd::setsid,
+};
+
+const CHILD: &str = "CLEARSCREEN_BACKGROUND_CHILD";
+
+fn run_child(test: &str, command: &mut Command, timeout: Duration) {
+	let mut child = command
+		.args(["--exact", test, "--ignored"])
+		.env(CHILD, "1")
+		.stdout(Stdio::null())
+		.spawn()
+		.unwrap();
+
+	// if the action isn’t stopped from being done, SIGTTOU stops the process instead
+	let start = Instant::now();
+	let status = loop {
+		if let Some(status) = child.try_wait().unwrap() {
+			break status;
+		}
+		if start.elapsed() > timeout {
+			child.kill().unwrap();
+			panic!("{test} didn’t finish");
+		}
+		thread::sleep(Duration::from_millis(10));
+	};
+	assert!(status.success(), "{test}: {status}");
+}
+
+#[test]
+fn background_process_group() {
+	let pty = openpty(None, None).unwrap();
+	run_child(
+		"session_child",
+		Command::new(env::current_exe().unwrap()).stdin(Stdio::from(pty.slave)),
+		Duration::from_secs(60),
+	);
+}
+
+/// Takes the pseudo-terminal on stdin as its controlling terminal, so it’s in the foreground, and
+/// runs `background_child` in another process group.
+#[test]
+#[ignore]
+fn session_child() {
+	if env::var_os(CHILD).is_none() {
+		return;
+	}
+
+	setsid().unwrap();
+	assert_eq!(unsafe { ioctl(0, TIOCSCTTY, 0) }, 0);
+
+	run_child(
+		"background_child",
+		Command::new(env::current_exe().unwrap()).process_group(0),
+		Duration::from_secs(30),
+	);
+}
+
+#[test]
+#[ignore]
+fn background_child() {
+	if env::var_os(CHILD).is_none() {
+		return;
+	}
+
+	let echo = || {
+		TermiosState::read()
+			.unwrap()
+			.local_flags
+			.contains(LocalFlags::ECHO)
+	};
+	assert!(echo());
+
+	// by default, nothing is done
+	assert!(matches!(
+		ClearScreen::VtCooked.clear(),
+		Err(Error::NotForeground)
+	));
+	assert!(echo());
+
+	let options = Options::default().background(Background::Skip);
+	ClearScreen::VtCooked.clear_with(&options).unwrap();
+	assert!(echo());
+
+	// VtCooked clears ECHO, among others
+	let options = Options::default().background(Background::BlockSigttou);
+	ClearScreen::VtCooked.clear_with(&options).unwrap();
+	assert!(!echo());
+}
@@ -0,0 +1,54 @@
+use clearscreen::expand_capability;
+use terminfo::Value;
+
+mod common;
+
+#[test]
+fn standard_capability() {
+	assert_eq!(
+		expand_capability(&common::database(&[]), "clear", &[]).unwrap(),
+		Some(b"\x1b[H\x1b[J".to_vec())
+	);
+}
+
+#[test]
+fn extended_capability() {
+	assert_eq!(
+		expand_capability(&common::database(&[]), "E3", &[]).unwrap(),
+		Some(b"\x1b[3J".to_vec())
+	);
+}
+
+#[test]
+fn parameters() {
+	assert_eq!(
+		expand_capability(
+			&common::database(&[("Ss", "\x1b[%p1%d q".into())]),
+			"Ss",
+			&[2.into()]
+		)
+		.unwrap(),
+		Some(b"\x1b[2 q".to_vec())
+	);
+}
+
+#[test]
+fn missing_capability() {
+	assert_eq!(
+		expand_capability(&common::database(&[]), "XM", &[]).unwrap(),
+		None
+	);
+}
+
+#[test]
+fn not_a_string() {
+	assert_eq!(
+		expand_capability(
+			&common::database(&[("cols", Value::Number(80))]),
+			"cols",
+			&[]
+		)
+		.unwrap(),
+		None
+	);
+}
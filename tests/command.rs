@@ -0,0 +1,221 @@
+//! External commands and their failures, with stand-ins for `tput` and a custom command.
+//!
+//! Everything is in one test, as it has to put the stand-in on the `PATH`.
+
+#![cfg(unix)]
+
+use std::{env, fs, os::unix::fs::PermissionsExt, time::Duration};
+
+use clearscreen::{ClearScreen, CommandLookup, Error, ExternalCommand, Options};
+
+const TPUT: &str = "#!/bin/sh
+if [ -n \"$TPUT_HANG\" ]; then exec sleep 5; fi
+case \"$1\" in
+	clear) printf 'cleared for %s' \"$TERM\" ;;
+	*) echo \"tput: cannot $1\" >&2; exit 3 ;;
+esac
+";
+
+const SITE_CLEAR: &str = "#!/bin/sh
+if [ \"$1\" = --fail ]; then echo 'site-clear: no terminal' >&2; exit 2; fi
+printf '%s for %s' \"$*\" \"$TERM\"
+";
+
+#[test]
+fn external_commands() {
+	let dir = env::temp_dir().join(format!("clearscreen-command-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let tput = dir.join("tput");
+	fs::write(&tput, TPUT).unwrap();
+	fs::set_permissions(&tput, fs::Permissions::from_mode(0o755)).unwrap();
+	let site_clear = dir.join("site-clear");
+	fs::write(&site_clear, SITE_CLEAR).unwrap();
+	fs::set_permissions(&site_clear, fs::Permissions::from_mode(0o755)).unwrap();
+	let path = env::var("PATH").unwrap_or_default();
+	env::set_var("PATH", format!("{}:{path}", dir.display()));
+
+	let options = Options::default().term("fixture");
+	let mut out = Vec::new();
+	ClearScreen::TputClear
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	assert_eq!(out, b"cleared for fixture");
+
+	let err = ClearScreen::TputReset
+		.clear_to_with(&mut Vec::new(), &options)
+		.unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"tput reset: exit status: 3 (tput: cannot reset)"
+	);
+	match err {
+		Error::Command {
+			argv,
+			status,
+			stderr,
+		} => {
+			assert_eq!(argv, ["tput", "reset"]);
+			assert_eq!(status.code(), Some(3));
+			assert_eq!(stderr, "tput: cannot reset\n");
+		}
+		other => panic!("{other:?}"),
+	}
+
+	let mut out = Vec::new();
+	ExternalCommand::new("site-clear")
+		.args(["--all", "--quiet"])
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	assert_eq!(out, b"--all --quiet for fixture");
+
+	let mut out = Vec::new();
+	ExternalCommand::new(&site_clear)
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	assert_eq!(out, b" for fixture");
+
+	let err = ExternalCommand::new("site-clear")
+		.arg("--fail")
+		.clear_to_with(&mut Vec::new(), &options)
+		.unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"site-clear --fail: exit status: 2 (site-clear: no terminal)"
+	);
+
+	assert!(matches!(
+		ExternalCommand::new("clearscreen-no-such-command").clear_to(&mut Vec::new()),
+		Err(Error::CommandNotFound { program }) if program == "clearscreen-no-such-command"
+	));
+
+	let setterm = ExternalCommand::setterm();
+	assert_eq!(setterm.get_program(), "setterm");
+	assert_eq!(setterm.get_args().collect::<Vec<_>>(), ["--clear", "all"]);
+	assert_eq!(
+		ExternalCommand::clear_command(true)
+			.get_args()
+			.collect::<Vec<_>>(),
+		["-x"]
+	);
+	assert_eq!(ExternalCommand::clear_command(false).get_args().count(), 0);
+
+	// restricted lookups
+	let writable = dir.join("writable");
+	fs::create_dir_all(&writable).unwrap();
+	fs::set_permissions(&writable, fs::Permissions::from_mode(0o777)).unwrap();
+	fs::copy(&site_clear, writable.join("unsafe-clear")).unwrap();
+	let loose_clear = dir.join("loose-clear");
+	fs::copy(&site_clear, &loose_clear).unwrap();
+	fs::set_permissions(&loose_clear, fs::Permissions::from_mode(0o777)).unwrap();
+	env::set_var(
+		"PATH",
+		format!("{}:{}:relative:{path}", writable.display(), dir.display()),
+	);
+
+	let trusted = options.clone().command_lookup(CommandLookup::TrustedPath);
+	let mut out = Vec::new();
+	ExternalCommand::new("site-clear")
+		.clear_to_with(&mut out, &trusted)
+		.unwrap();
+	assert_eq!(out, b" for fixture");
+
+	let rejected = |cmd: ExternalCommand, options: &Options| match cmd
+		.clear_to_with(&mut Vec::new(), options)
+	{
+		Err(Error::CommandRejected { program, reason }) => (program, reason),
+		other => panic!("{other:?}"),
+	};
+
+	let (program, reason) = rejected(ExternalCommand::new("unsafe-clear"), &trusted);
+	assert_eq!(program, "unsafe-clear");
+	assert!(reason.starts_with("not found in "), "{reason}");
+	assert!(!reason.contains(&*writable.to_string_lossy()), "{reason}");
+	assert!(!reason.contains("relative"), "{reason}");
+
+	let (_, reason) = rejected(ExternalCommand::new("loose-clear"), &trusted);
+	assert_eq!(
+		reason,
+		format!("{} is world-writable", loose_clear.display())
+	);
+
+	let (_, reason) = rejected(ExternalCommand::new("./site-clear"), &trusted);
+	assert_eq!(reason, "relative paths are not allowed");
+
+	// absolute paths are taken as they are
+	ExternalCommand::new(&site_clear)
+		.clear_to_with(&mut Vec::new(), &trusted)
+		.unwrap();
+
+	let in_dir = options
+		.clone()
+		.command_lookup(CommandLookup::Dirs(vec![dir.clone()]));
+	let err = ClearScreen::TputReset
+		.clear_to_with(&mut Vec::new(), &in_dir)
+		.unwrap_err();
+	match err {
+		Error::Command { argv, .. } => {
+			assert_eq!(argv, [tput.display().to_string(), "reset".into()])
+		}
+		other => panic!("{other:?}"),
+	}
+
+	let (_, reason) = rejected(
+		ExternalCommand::new("site-clear"),
+		&options
+			.clone()
+			.command_lookup(CommandLookup::Dirs(vec!["relative".into()])),
+	);
+	assert_eq!(reason, "relative is not an absolute directory");
+
+	let nowhere = options
+		.clone()
+		.command_lookup(CommandLookup::Dirs(Vec::new()));
+	let (program, _) = rejected(ExternalCommand::new("tput"), &nowhere);
+	assert_eq!(program, "tput");
+	let mut out = Vec::new();
+	ClearScreen::TputClear
+		.clear_to_with(&mut out, &nowhere.clone().command_path("tput", &tput))
+		.unwrap();
+	assert_eq!(out, b"cleared for fixture");
+	let (_, reason) = rejected(
+		ExternalCommand::new("tput"),
+		&nowhere.command_path("tput", "tput"),
+	);
+	assert_eq!(reason, "relative paths are not allowed");
+
+	for options in [&options, &trusted] {
+		match ClearScreen::Cls.clear_to_with(&mut Vec::new(), options) {
+			Err(Error::CommandRejected { program, .. }) => assert_eq!(program, "cmd.exe"),
+			other => panic!("{other:?}"),
+		}
+	}
+
+	// pinned absolute paths are checked too
+	let (program, reason) = rejected(
+		ExternalCommand::new("loose-clear"),
+		&trusted.clone().command_path("loose-clear", &loose_clear),
+	);
+	assert_eq!(program, "loose-clear");
+	assert_eq!(
+		reason,
+		format!("{} is world-writable", loose_clear.display())
+	);
+	let (_, reason) = rejected(ExternalCommand::new(dir.join("missing")), &trusted);
+	assert_eq!(
+		reason,
+		format!("{} doesn’t exist", dir.join("missing").display())
+	);
+
+	env::set_var("PATH", format!("{}:{path}", dir.display()));
+	env::set_var("TPUT_HANG", "1");
+	let timeout = Duration::from_millis(100);
+	match ClearScreen::TputClear.clear_to_with(&mut Vec::new(), &options.command_timeout(timeout)) {
+		Err(Error::CommandTimeout { argv, timeout: t }) => {
+			assert_eq!(argv, ["tput", "clear"]);
+			assert_eq!(t, timeout);
+		}
+		other => panic!("{other:?}"),
+	}
+
+	fs::remove_dir_all(dir).unwrap();
+}
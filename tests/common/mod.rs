@@ -0,0 +1,30 @@
+//! Helpers shared by the integration tests.
+
+#![allow(dead_code)]
+
+use terminfo::{Database, Value};
+
+/// A terminfo entry named `fixture`, with xterm’s `clear`, `E3`, `home`, `rmcup` and `sgr0`, and
+/// the given capabilities added or overridden.
+pub fn database(caps: &[(&str, Value)]) -> Database {
+	// the builder keeps the first value given for a capability
+	let mut all = caps.to_vec();
+	all.extend([
+		("clear", Value::from("\x1b[H\x1b[J")),
+		("E3", "\x1b[3J".into()),
+		("home", "\x1b[H".into()),
+		("rmcup", "\x1b[?1049l".into()),
+		("sgr0", "\x1b[m".into()),
+	]);
+	entry("fixture", &all)
+}
+
+/// A terminfo entry with only the given capabilities.
+pub fn entry(name: &str, caps: &[(&str, Value)]) -> Database {
+	let mut builder = Database::new();
+	builder.name(name);
+	for (cap, value) in caps {
+		builder.raw(cap, value.clone());
+	}
+	builder.build().unwrap()
+}
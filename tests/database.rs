@@ -0,0 +1,87 @@
+use clearscreen::{ClearScreen, Options};
+
+mod common;
+
+fn clear_with_db(cs: ClearScreen) -> Vec<u8> {
+	let mut out = Vec::new();
+	cs.clear_to_with_db(&common::database(&[]), &mut out)
+		.unwrap();
+	out
+}
+
+#[test]
+fn terminfo() {
+	assert_eq!(clear_with_db(ClearScreen::Terminfo), b"\x1b[H\x1b[J\x1b[3J");
+}
+
+#[test]
+fn terminfo_screen() {
+	assert_eq!(clear_with_db(ClearScreen::TerminfoScreen), b"\x1b[H\x1b[J");
+}
+
+#[test]
+fn terminfo_scrollback() {
+	assert_eq!(clear_with_db(ClearScreen::TerminfoScrollback), b"\x1b[3J");
+}
+
+#[test]
+fn terminfo_capability() {
+	assert_eq!(
+		clear_with_db(ClearScreen::TerminfoCapability("sgr0")),
+		b"\x1b[m"
+	);
+}
+
+#[test]
+fn terminfo_recover() {
+	let mut out = Vec::new();
+	let applied = ClearScreen::terminfo_recover_to_with(
+		&mut out,
+		&Options::default().database(common::database(&[])),
+	)
+	.unwrap();
+	assert_eq!(applied, ["rmcup", "sgr0"]);
+	assert_eq!(out, b"\x1b[?1049l\x1b[m");
+}
+
+#[test]
+fn missing_capability() {
+	let info = common::entry("empty", &[]);
+	let err = ClearScreen::TerminfoScreen
+		.clear_to_with_db(&info, &mut Vec::new())
+		.unwrap_err();
+	assert_eq!(
+		err.to_string(),
+		"required terminfo capability not available: clear (for empty)"
+	);
+}
+
+#[test]
+fn other_variants_unaffected() {
+	assert_eq!(
+		clear_with_db(ClearScreen::XtermClear),
+		b"\x1b[H\x1b[2J\x1b[3J"
+	);
+}
+
+#[test]
+fn unknown_term() {
+	let options = Options::default().term("no-such-terminal");
+	assert!(ClearScreen::Terminfo
+		.clear_to_with(&mut Vec::new(), &options)
+		.is_err());
+}
+
+#[test]
+fn for_term() {
+	assert_eq!(ClearScreen::for_term("xterm-kitty"), ClearScreen::VtRis);
+	assert_eq!(ClearScreen::for_term("rxvt-unicode"), ClearScreen::VtRis);
+	assert_eq!(
+		ClearScreen::for_term("tmux-256color"),
+		ClearScreen::XtermClear
+	);
+	assert_eq!(
+		ClearScreen::for_term("no-such-terminal"),
+		ClearScreen::XtermClear
+	);
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "embedded-terminfo")]
+
+//! Everything is in one test, as it has to point the process environment at an empty directory.
+
+use std::{env, fs};
+
+use clearscreen::{terminfo_source, ClearScreen, Options, TerminfoEntry, TerminfoSource};
+
+#[test]
+fn embedded_entries() {
+	let dir = env::temp_dir().join(format!("clearscreen-embedded-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	env::set_var("TERMINFO", &dir);
+	env::set_var("TERMINFO_DIRS", &dir);
+	env::set_var("HOME", &dir);
+	env::set_var("PREFIX", &dir);
+
+	// not in ncurses, so not in the system directories unless kitty is installed
+	env::set_var("TERM", "xterm-kitty");
+	assert_eq!(terminfo_source().unwrap(), TerminfoSource::Embedded);
+	assert_eq!(TerminfoEntry::from_env().unwrap().entry, "xterm-kitty");
+
+	let mut out = Vec::new();
+	ClearScreen::TerminfoScreen.clear_to(&mut out).unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[2J");
+
+	// this may also be found on the system, but the output is the same either way
+	let mut out = Vec::new();
+	ClearScreen::Terminfo
+		.clear_to_with(&mut out, &Options::default().term("alacritty"))
+		.unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[2J\x1b[3J");
+
+	assert_eq!(ClearScreen::for_term("alacritty"), ClearScreen::Terminfo);
+	assert_eq!(ClearScreen::for_term("foot"), ClearScreen::Terminfo);
+
+	fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,43 @@
+use clearscreen::{Error, TerminfoEntry};
+
+// the system may or may not have these entries, so either outcome is checked
+
+fn check_chain(chain: &[&str]) {
+	match TerminfoEntry::resolve(chain[0]) {
+		Ok(entry) => {
+			assert_eq!(entry.term, chain[0]);
+			assert!(chain.contains(&entry.entry.as_str()), "{}", entry.entry);
+			assert_eq!(entry.is_fallback(), entry.entry != chain[0]);
+		}
+		Err(Error::TerminfoNotFound {
+			term,
+			tried,
+			searched,
+		}) => {
+			assert_eq!(term, chain[0]);
+			assert_eq!(tried, chain);
+			assert!(searched.iter().any(|dir| dir.ends_with("share/terminfo")));
+		}
+		Err(err) => panic!("{err}"),
+	}
+}
+
+#[test]
+fn kitty() {
+	check_chain(&["xterm-kitty", "xterm-256color", "xterm"]);
+}
+
+#[test]
+fn tmux() {
+	check_chain(&["tmux-256color", "screen-256color", "tmux", "screen"]);
+}
+
+#[test]
+fn urxvt() {
+	check_chain(&["rxvt-unicode-256color", "rxvt-unicode", "rxvt"]);
+}
+
+#[test]
+fn not_found() {
+	check_chain(&["no-such-terminal", "no-such", "no"]);
+}
@@ -0,0 +1,128 @@
+use std::{borrow::Cow, io::Write};
+
+use clearscreen::{Capabilities, ClearScreen, ClearStrategy, Error, ErrorKind, Fallback, Options};
+
+mod common;
+
+/// Options with a terminfo database which has no capabilities.
+fn empty_terminfo() -> Options {
+	Options::default().database(common::entry("empty", &[]))
+}
+
+#[derive(Debug)]
+struct Broken;
+
+impl ClearStrategy for Broken {
+	fn name(&self) -> Cow<'_, str> {
+		"Broken".into()
+	}
+
+	fn capabilities(&self) -> Capabilities {
+		Capabilities::default()
+	}
+
+	fn clear_to_with(&self, _w: &mut dyn Write, _options: &Options) -> Result<(), Error> {
+		Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "broken pipe").into())
+	}
+}
+
+/// Writes some output, then fails as if a command was missing.
+#[derive(Debug)]
+struct Partial;
+
+impl ClearStrategy for Partial {
+	fn name(&self) -> Cow<'_, str> {
+		"Partial".into()
+	}
+
+	fn capabilities(&self) -> Capabilities {
+		Capabilities::default()
+	}
+
+	fn clear_to_with(&self, w: &mut dyn Write, _options: &Options) -> Result<(), Error> {
+		w.write_all(b"partial")?;
+		Err(Error::CommandNotFound {
+			program: "partial-clear".into(),
+		})
+	}
+}
+
+#[test]
+fn falls_back() {
+	let chain = Fallback::new(ClearScreen::TerminfoScrollback).or(ClearScreen::XtermClear);
+	assert!(chain.used().is_none());
+
+	let mut out = Vec::new();
+	let used = chain
+		.clear_to_with_used(&mut out, &empty_terminfo())
+		.unwrap();
+	assert_eq!(used.name(), "XtermClear");
+	assert_eq!(chain.used().unwrap().name(), "XtermClear");
+	assert_eq!(out, b"\x1b[H\x1b[2J\x1b[3J");
+}
+
+#[test]
+fn first_success() {
+	let chain = Fallback::new(ClearScreen::VtRis).or(ClearScreen::XtermClear);
+	let mut out = Vec::new();
+	chain.clear_to(&mut out).unwrap();
+	assert_eq!(chain.used().unwrap().name(), "VtRis");
+	assert_eq!(out, b"\x1bc");
+}
+
+#[test]
+fn other_errors_stop() {
+	let chain = Fallback::new(Broken).or(ClearScreen::XtermClear);
+	let mut out = Vec::new();
+	assert!(matches!(chain.clear_to(&mut out), Err(Error::Io(_))));
+	assert!(chain.used().is_none());
+	assert!(out.is_empty());
+}
+
+#[test]
+fn discards_partial_output() {
+	let chain = Fallback::new(Partial).or(ClearScreen::XtermClear);
+	let mut out = Vec::new();
+	chain.clear_to_with(&mut out, &Options::default()).unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[2J\x1b[3J");
+	assert_eq!(chain.used().unwrap().name(), "XtermClear");
+}
+
+#[test]
+fn custom_kinds() {
+	let chain = Fallback::new(ClearScreen::TerminfoScrollback)
+		.or(ClearScreen::XtermClear)
+		.on([ErrorKind::TerminfoNotFound]);
+	match chain.clear_to_with(&mut Vec::new(), &empty_terminfo()) {
+		Err(err) => assert_eq!(err.kind(), ErrorKind::CapabilityNotFound),
+		Ok(()) => panic!("fell back on a missing capability"),
+	}
+}
+
+#[test]
+fn last_error() {
+	let chain = Fallback::new(ClearScreen::TerminfoScreen).or(ClearScreen::TerminfoScrollback);
+	assert!(matches!(
+		chain.clear_to_with(&mut Vec::new(), &empty_terminfo()),
+		Err(Error::TerminfoCapNotFound {
+			cap: "E3",
+			entry: None,
+			..
+		})
+	));
+}
+
+#[test]
+fn default_chain() {
+	let chain = Fallback::default();
+	let names = chain
+		.strategies()
+		.map(|strategy| strategy.name().into_owned())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		names.first(),
+		Some(&ClearScreen::default().name().into_owned())
+	);
+	assert_eq!(names.last().map(String::as_str), Some("XtermClear"));
+	assert_eq!(chain.name(), names.join(" or "));
+}
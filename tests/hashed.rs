@@ -0,0 +1,183 @@
+//! Reading terminfo entries from a hashed database, as the BSDs ship.
+//!
+//! The database is built here by hand, following the layout that the 4.4BSD `db` library writes.
+//! Everything is in one test, as it has to point the process environment at the database.
+
+use std::{env, fs};
+
+use clearscreen::{terminfo_source, ClearScreen, TerminfoSource};
+
+const BSIZE: usize = 256;
+
+// markers in the second slot of a pair
+const OVFLPAGE: u16 = 0;
+const FULL_KEY: u16 = 2;
+const FULL_KEY_DATA: u16 = 3;
+
+/// A compiled entry in the ncurses 6.1+ format, with 32-bit numbers and extended capabilities.
+fn entry() -> Vec<u8> {
+	let names = b"fixture|fx|Fixture terminal\0";
+	let table = b"\x1b[H\x1b[2J\0";
+
+	let mut entry = Vec::new();
+	for n in [0o1036, names.len() as i16, 0, 1, 6, table.len() as i16] {
+		entry.extend(n.to_le_bytes());
+	}
+	entry.extend(names);
+	entry.extend(100_000_i32.to_le_bytes()); // cols
+	for offset in [-1_i16, -1, -1, -1, -1, 0] {
+		entry.extend(offset.to_le_bytes()); // ..., clear
+	}
+	entry.extend(table);
+
+	// E3, and a long string to make the record span several pages
+	let mut ext_table = b"\x1b[3J\0".to_vec();
+	ext_table.extend([b'x'; 400]);
+	ext_table.extend(b"\0E3\0XX\0");
+	for n in [0, 0, 2, 4, ext_table.len() as i16, 0, 5, 0, 3] {
+		entry.extend(n.to_le_bytes());
+	}
+	entry.extend(ext_table);
+	entry
+}
+
+fn header(overflow_pages: u32) -> Vec<u8> {
+	let mut header = Vec::new();
+	let mut words = vec![
+		0x061561,
+		2,
+		1234,
+		BSIZE as u32,
+		8,
+		256,
+		256,
+		8,
+		1,
+		0,
+		1,
+		3,
+		1,
+		8,
+		3,
+		1,
+		0,
+	];
+	words.extend([0, overflow_pages]);
+	words.resize(17 + 32, 0);
+	for word in words {
+		header.extend(word.to_be_bytes());
+	}
+	header.resize(BSIZE, 0);
+	header
+}
+
+/// Pages 1 and 2 are the two buckets, and overflow pages come after.
+fn overflow_addr(page: usize) -> u16 {
+	(1 << 11) | (page - 2) as u16
+}
+
+/// Builds a page from its slots, followed by the free space, and chunks placed at offsets.
+fn page(slots: &[u16], free: u16, chunks: &[(u16, &[u8])]) -> Vec<u8> {
+	let offset = chunks
+		.iter()
+		.map(|(off, _)| *off)
+		.min()
+		.unwrap_or(BSIZE as u16);
+	let header = [&[slots.len() as u16], slots, &[free, offset]].concat();
+
+	let mut page = vec![0; BSIZE];
+	for (i, slot) in header.iter().enumerate() {
+		page[i * 2..i * 2 + 2].copy_from_slice(&slot.to_le_bytes());
+	}
+	for (offset, chunk) in chunks {
+		page[*offset as usize..][..chunk.len()].copy_from_slice(chunk);
+	}
+	page
+}
+
+fn pairs_page(pairs: &[(&[u8], &[u8])]) -> Vec<u8> {
+	let mut end = BSIZE;
+	let mut slots = Vec::new();
+	let mut chunks = Vec::new();
+	for (key, data) in pairs {
+		let key_off = (end - key.len()) as u16;
+		let data_off = key_off - data.len() as u16;
+		slots.extend([key_off, data_off]);
+		chunks.extend([(key_off, *key), (data_off, *data)]);
+		end = data_off as usize;
+	}
+	page(&slots, 1, &chunks)
+}
+
+fn database() -> Vec<u8> {
+	let names: &[u8] = b"fixture|fx|Fixture terminal";
+	let alias = [&[2], names].concat();
+	let record = [&[0], &entry()[..]].concat();
+
+	let mut pages = vec![
+		// bucket 0 is empty apart from a link to its overflow chain
+		page(&[overflow_addr(3), OVFLPAGE], 1, &[]),
+		pairs_page(&[(b"fixture", &alias)]),
+	];
+
+	// the record is too big for a page: its key and the start of its data go on the first page
+	let key_off = (BSIZE - names.len()) as u16;
+	let meta = 7 * 2;
+	let (first, mut rest) = record.split_at(key_off as usize - meta);
+	pages.push(page(
+		&[key_off, FULL_KEY_DATA, overflow_addr(4), meta as u16],
+		0,
+		&[(key_off, names), (meta as u16, first)],
+	));
+
+	// then the rest of the data, and finally a link to the rest of the bucket
+	while !rest.is_empty() {
+		let next = overflow_addr(pages.len() + 2);
+		let (chunk, more) = rest.split_at(rest.len().min(BSIZE - meta));
+		let off = (BSIZE - chunk.len()) as u16;
+		let marker = if more.is_empty() {
+			FULL_KEY_DATA
+		} else {
+			FULL_KEY
+		};
+		pages.push(page(&[off, marker, next, OVFLPAGE], 1, &[(off, chunk)]));
+		rest = more;
+	}
+	pages.push(pairs_page(&[(b"fx", &alias)]));
+
+	let mut file = header(pages.len() as u32 - 2);
+	file.extend(pages.concat());
+	file
+}
+
+#[test]
+fn hashed_database() {
+	let dir = env::temp_dir().join(format!("clearscreen-hashed-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let path = dir.join("terminfo.db");
+	fs::write(&path, database()).unwrap();
+	env::set_var("TERMINFO", &path);
+
+	for term in ["fixture", "fx"] {
+		env::set_var("TERM", term);
+		assert_eq!(
+			terminfo_source().unwrap(),
+			TerminfoSource::Hashed(path.clone())
+		);
+
+		let mut out = Vec::new();
+		ClearScreen::TerminfoScreen.clear_to(&mut out).unwrap();
+		assert_eq!(out, b"\x1b[H\x1b[2J", "clear for {term}");
+
+		let mut out = Vec::new();
+		ClearScreen::TerminfoScrollback.clear_to(&mut out).unwrap();
+		assert_eq!(out, b"\x1b[3J", "E3 for {term}");
+	}
+
+	env::set_var("TERM", "missing");
+	assert!(ClearScreen::TerminfoScreen
+		.clear_to(&mut Vec::new())
+		.is_err());
+
+	fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,66 @@
+//! Reading the hashed database that the BSDs ship, where there is one, checked against ncurses’
+//! own `tput` reading the same database.
+//!
+//! Unlike the database built by hand in `hashed.rs`, this is written by the system’s `tic` and its
+//! `db` library, so it catches misreadings of the format. Elsewhere, the test does nothing.
+
+use std::{path::Path, process::Command};
+
+use clearscreen::{ClearScreen, Options, TerminfoEntry, TerminfoSource};
+use terminfo::Value;
+
+const DATABASE: &str = "/usr/share/misc/terminfo.db";
+
+/// Entries without padding, which `tput` would write differently when not on a terminal.
+const ENTRIES: &[&str] = &["xterm", "xterm-256color", "screen"];
+
+const STRINGS: &[&str] = &["clear", "smcup", "rmcup", "sgr0", "cnorm", "el", "bold"];
+
+fn tput(term: &str, cap: &str) -> Option<Vec<u8>> {
+	let output = Command::new("tput").args(["-T", term, cap]).output().ok()?;
+	output.status.success().then_some(output.stdout)
+}
+
+#[test]
+fn system_hashed_database() {
+	if !Path::new(DATABASE).is_file() {
+		eprintln!("no hashed database at {DATABASE}, skipping");
+		return;
+	}
+
+	for &term in ENTRIES {
+		let entry = TerminfoEntry::resolve(term).unwrap();
+		if entry.source != TerminfoSource::Hashed(DATABASE.into()) {
+			eprintln!("{term} is read from {}, skipping", entry.source);
+			continue;
+		}
+
+		for &cap in STRINGS {
+			let Some(expected) = tput(term, cap) else {
+				continue;
+			};
+
+			let mut out = Vec::new();
+			ClearScreen::TerminfoCapability(cap)
+				.clear_to_with(&mut out, &Options::default().term(term))
+				.unwrap();
+			assert_eq!(
+				String::from_utf8_lossy(&out),
+				String::from_utf8_lossy(&expected),
+				"{term} {cap}"
+			);
+		}
+
+		for cap in ["cols", "lines", "colors"] {
+			let Some(expected) = tput(term, cap) else {
+				continue;
+			};
+			let expected: i32 = String::from_utf8_lossy(&expected).trim().parse().unwrap();
+			assert_eq!(
+				entry.database.raw(cap),
+				Some(&Value::Number(expected)),
+				"{term} {cap}"
+			);
+		}
+	}
+}
@@ -0,0 +1,123 @@
+//! Reading terminfo entries through `infocmp`, with a stand-in which prints a fixture entry.
+//!
+//! Everything is in one test, as it has to put the stand-in on the `PATH`.
+
+#![cfg(unix)]
+
+use std::{env, fs, os::unix::fs::PermissionsExt};
+
+use clearscreen::{ClearScreen, Error, Options, TerminfoEntry, TerminfoSource};
+
+/// Prints the output of ncurses 6.5’s `infocmp -x -1 fixture`, for an entry compiled with `tic -x`,
+/// and an entry with a malformed capability.
+const INFOCMP: &str = r#"#!/bin/sh
+case "$3" in
+fixture) cat <<'EOF'
+#	Reconstructed via infocmp from file: /tmp/tis/f/fixture
+fixture|fix|a terminal which only infocmp knows,
+	am,
+	cols#80,
+	lines#24,
+	clear=\E[H\E[2J$<5>,
+	el=\E[K,
+	kf1=\EOP,
+	rs1=\Ec\E]104\E\\,
+	smcup=\E[?1049h\,,
+	E3=\E[3J,
+	Ms=\E]52;%p1%s;%p2%s\E\\,
+EOF
+;;
+malformed) cat <<'EOF'
+malformed|an entry with a bad number,
+	cols#eighty,
+	clear=\E[H\E[2J,
+EOF
+;;
+*)
+	echo "infocmp: couldn't open terminfo file for $3" >&2
+	exit 1
+;;
+esac
+"#;
+
+#[test]
+fn infocmp_entries() {
+	let dir = env::temp_dir().join(format!("clearscreen-infocmp-{}", std::process::id()));
+	fs::create_dir_all(&dir).unwrap();
+	let infocmp = dir.join("infocmp");
+	fs::write(&infocmp, INFOCMP).unwrap();
+	fs::set_permissions(&infocmp, fs::Permissions::from_mode(0o755)).unwrap();
+	let path = env::var("PATH").unwrap_or_default();
+	env::set_var("PATH", format!("{}:{path}", dir.display()));
+
+	// not asked for by default
+	match TerminfoEntry::resolve("fixture") {
+		Err(Error::TerminfoNotFound { term, .. }) => assert_eq!(term, "fixture"),
+		other => panic!("{other:?}"),
+	}
+
+	let options = Options::default().term("fixture").infocmp(true);
+	let entry = TerminfoEntry::resolve_with("fixture", &options).unwrap();
+	assert_eq!(entry.entry, "fixture");
+	assert_eq!(entry.source, TerminfoSource::Infocmp);
+	assert_eq!(entry.database.name(), "fixture");
+	assert_eq!(entry.database.aliases(), ["fix"]);
+	assert_eq!(
+		entry.database.description(),
+		"a terminal which only infocmp knows"
+	);
+
+	let raw = |name| entry.database.raw(name).cloned();
+	assert_eq!(raw("am"), Some(terminfo::Value::True));
+	assert_eq!(raw("cols"), Some(terminfo::Value::Number(80)));
+	assert_eq!(raw("lines"), Some(terminfo::Value::Number(24)));
+	assert_eq!(raw("el"), Some(terminfo::Value::String(b"\x1b[K".to_vec())));
+	assert_eq!(
+		raw("rs1"),
+		Some(terminfo::Value::String(b"\x1bc\x1b]104\x1b\\".to_vec()))
+	);
+	assert_eq!(
+		raw("smcup"),
+		Some(terminfo::Value::String(b"\x1b[?1049h,".to_vec()))
+	);
+	assert_eq!(
+		raw("Ms"),
+		Some(terminfo::Value::String(
+			b"\x1b]52;%p1%s;%p2%s\x1b\\".to_vec()
+		))
+	);
+
+	// a malformed capability is skipped, keeping the rest of the entry
+	let entry = TerminfoEntry::resolve_with("malformed", &options).unwrap();
+	assert_eq!(entry.database.raw("cols"), None);
+	assert_eq!(
+		entry.database.raw("clear").cloned(),
+		Some(terminfo::Value::String(b"\x1b[H\x1b[2J".to_vec()))
+	);
+
+	// used by the terminfo variants, with padding applied rather than printed
+	let mut out = Vec::new();
+	ClearScreen::TerminfoScreen
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[2J");
+
+	let mut out = Vec::new();
+	ClearScreen::TerminfoScrollback
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	assert_eq!(out, b"\x1b[3J");
+
+	// a failing infocmp is the same as no entry
+	match TerminfoEntry::resolve_with("unknown-to-infocmp", &options) {
+		Err(Error::TerminfoNotFound { tried, .. }) => {
+			assert_eq!(
+				tried.first().map(String::as_str),
+				Some("unknown-to-infocmp")
+			)
+		}
+		other => panic!("{other:?}"),
+	}
+
+	fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,97 @@
+use clearscreen::{ClearScreen, Error, Options, Overlay};
+
+mod common;
+
+fn clear(cs: ClearScreen, options: Options) -> Result<Vec<u8>, Error> {
+	let mut out = Vec::new();
+	let info = common::entry("xterm-fixture", &[("clear", "\x1b[H\x1b[2J".into())]);
+	cs.clear_to_with(&mut out, &options.database(info))?;
+	Ok(out)
+}
+
+#[test]
+fn adds_capability() {
+	let options = Options::default().overlay(Overlay::new().set("xterm*", "E3", "\x1b[3J"));
+	assert_eq!(
+		clear(ClearScreen::Terminfo, options.clone()).unwrap(),
+		b"\x1b[H\x1b[2J\x1b[3J"
+	);
+	assert_eq!(
+		clear(ClearScreen::TerminfoScrollback, options).unwrap(),
+		b"\x1b[3J"
+	);
+}
+
+#[test]
+fn overrides_capability() {
+	let options =
+		Options::default().overlay(Overlay::new().set("xterm-fixture", "clear_screen", "\x1b[2J"));
+	assert_eq!(
+		clear(ClearScreen::TerminfoScreen, options).unwrap(),
+		b"\x1b[2J"
+	);
+}
+
+#[test]
+fn cancels_capability() {
+	let options = Options::default().overlay(Overlay::new().cancel("*", "clear"));
+	assert!(clear(ClearScreen::TerminfoScreen, options).is_err());
+}
+
+#[test]
+fn later_rules_win() {
+	let overlay = Overlay::new()
+		.set("xterm*", "E3", "\x1b[3J")
+		.cancel("xterm-fixture", "E3")
+		.extend(Overlay::new().set("x?erm-*", "E3", "\x1b[3;J"));
+	let options = Options::default().overlay(overlay);
+	assert_eq!(
+		clear(ClearScreen::TerminfoScrollback, options).unwrap(),
+		b"\x1b[3;J"
+	);
+}
+
+#[test]
+fn matches_term_name() {
+	let overlay = Overlay::new().set("screen|foot", "E3", "\x1b[3J");
+	let options = Options::default().overlay(overlay);
+	assert!(clear(ClearScreen::TerminfoScrollback, options.clone()).is_err());
+	assert_eq!(
+		clear(ClearScreen::TerminfoScrollback, options.term("foot")).unwrap(),
+		b"\x1b[3J"
+	);
+}
+
+#[test]
+fn parses_source_format() {
+	let overlay: Overlay = "
+		# comment
+		xterm*: E3=\\E[3J, clear=^[[H\\,\\s\\0\\177
+		other: bce, colors#0x100, sgr0@
+	"
+	.parse()
+	.unwrap();
+	let expected = Overlay::new()
+		.set("xterm*", "E3", "\x1b[3J")
+		.set("xterm*", "clear", b"\x1b[H, \x80\x7f".as_slice())
+		.set("other", "bce", ())
+		.set("other", "colors", 256)
+		.cancel("other", "sgr0");
+	assert_eq!(overlay, expected);
+}
+
+#[test]
+fn parse_errors() {
+	for (text, line) in [
+		("xterm E3=\\E[3J", 1),
+		("\n: E3", 2),
+		("xterm: cols#eighty", 1),
+		("xterm: E3=\\", 1),
+		("xterm: =x", 1),
+	] {
+		match text.parse::<Overlay>() {
+			Err(Error::OverlayFormat { line: l, .. }) => assert_eq!(l, line, "{text:?}"),
+			other => panic!("{text:?}: {other:?}"),
+		}
+	}
+}
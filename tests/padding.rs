@@ -0,0 +1,201 @@
+use std::{env, fs};
+
+use clearscreen::{ClearScreen, Options, Padding};
+use terminfo::{Database, Value};
+
+mod common;
+
+/// The fixture with delays in its capabilities, and the given capabilities on top.
+fn database(extra: &[(&str, Value)]) -> Database {
+	let mut caps = extra.to_vec();
+	caps.extend([
+		("clear", Value::from("\x1b[H\x1b[J$<50>")),
+		("E3", "\x1b[3J$<5/>".into()),
+		("cub1", "\x08$<2*>".into()),
+		("pb", Value::Number(9600)),
+	]);
+	common::database(&caps)
+}
+
+fn clear(cs: ClearScreen, info: Database, options: Options) -> Vec<u8> {
+	let mut out = Vec::new();
+	cs.clear_to_with(&mut out, &options.database(info).baud_rate(38400))
+		.unwrap();
+	out
+}
+
+fn padded(seq: &[u8], count: usize, pad: u8) -> Vec<u8> {
+	let mut out = seq.to_vec();
+	out.resize(seq.len() + count, pad);
+	out
+}
+
+#[test]
+fn pads_at_baud_rate() {
+	// 50ms at 38400 baud and 9 bits per character
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScreen,
+			database(&[]),
+			Options::default()
+		),
+		padded(b"\x1b[H\x1b[J", 213, 0)
+	);
+}
+
+#[test]
+fn pad_character() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScreen,
+			database(&[("pad", "*".into())]),
+			Options::default()
+		),
+		padded(b"\x1b[H\x1b[J", 213, b'*')
+	);
+}
+
+#[test]
+fn below_padding_baud_rate() {
+	let options = Options::default().baud_rate(2400);
+	let mut out = Vec::new();
+	ClearScreen::TerminfoScreen
+		.clear_to_with(&mut out, &options.database(database(&[])))
+		.unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[J");
+}
+
+#[test]
+fn xon_xoff() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScreen,
+			database(&[("xon", Value::True)]),
+			Options::default()
+		),
+		b"\x1b[H\x1b[J"
+	);
+}
+
+#[test]
+fn mandatory() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScrollback,
+			database(&[("xon", Value::True)]),
+			Options::default()
+		),
+		padded(b"\x1b[3J", 21, 0)
+	);
+}
+
+#[test]
+fn proportional() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoCapability("cub1"),
+			database(&[]),
+			Options::default()
+		),
+		padded(b"\x08", 8, 0)
+	);
+}
+
+#[test]
+fn off() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScrollback,
+			database(&[]),
+			Options::default().padding(Padding::Off)
+		),
+		b"\x1b[3J"
+	);
+}
+
+#[test]
+fn always() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScreen,
+			database(&[("xon", Value::True)]),
+			Options::default().padding(Padding::Always)
+		),
+		padded(b"\x1b[H\x1b[J", 213, 0)
+	);
+}
+
+#[test]
+fn not_a_delay() {
+	assert_eq!(
+		clear(
+			ClearScreen::TerminfoScreen,
+			common::entry("fixture", &[("clear", "$<x>\x1b[J$".into())]),
+			Options::default().padding(Padding::Always)
+		),
+		b"$<x>\x1b[J$"
+	);
+}
+
+/// Run by `pads_at_terminal_rate`, with stdin on a pseudo-terminal set to a slow rate.
+#[test]
+#[ignore]
+fn terminal_rate_child() {
+	let Some(path) = env::var_os("CLEARSCREEN_PADDING_OUT") else {
+		return;
+	};
+
+	let mut out = Vec::new();
+	let info = database(&[("pb", Value::Number(300))]);
+	ClearScreen::TerminfoScreen
+		.clear_to_with(&mut out, &Options::default().database(info))
+		.unwrap();
+	fs::write(path, out).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn pads_at_terminal_rate() {
+	use std::process::{Command, Stdio};
+
+	use nix::{
+		pty::openpty,
+		sys::termios::{cfsetospeed, tcgetattr, tcsetattr, BaudRate, SetArg},
+	};
+
+	// 50ms at 9 bits per character; the BSDs and macOS have rates other platforms don’t
+	#[cfg(any(
+		target_os = "dragonfly",
+		target_os = "freebsd",
+		target_os = "netbsd",
+		target_os = "openbsd",
+		target_vendor = "apple"
+	))]
+	let (speed, count) = (BaudRate::B7200, 40);
+	#[cfg(not(any(
+		target_os = "dragonfly",
+		target_os = "freebsd",
+		target_os = "netbsd",
+		target_os = "openbsd",
+		target_vendor = "apple"
+	)))]
+	let (speed, count) = (BaudRate::B1800, 10);
+
+	let pty = openpty(None, None).unwrap();
+	let mut termios = tcgetattr(&pty.slave).unwrap();
+	cfsetospeed(&mut termios, speed).unwrap();
+	tcsetattr(&pty.slave, SetArg::TCSANOW, &termios).unwrap();
+
+	let path = env::temp_dir().join(format!("clearscreen-padding-{}", std::process::id()));
+	let status = Command::new(env::current_exe().unwrap())
+		.args(["--exact", "terminal_rate_child", "--ignored"])
+		.env("CLEARSCREEN_PADDING_OUT", &path)
+		.stdin(Stdio::from(pty.slave))
+		.stdout(Stdio::null())
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	assert_eq!(fs::read(&path).unwrap(), padded(b"\x1b[H\x1b[J", count, 0));
+	fs::remove_file(path).unwrap();
+}
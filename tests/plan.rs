@@ -0,0 +1,192 @@
+use std::{
+	ffi::OsString,
+	time::{Duration, Instant},
+};
+
+use clearscreen::{
+	Backend, ClearActions, ClearScreen, CommandLookup, Error, Options, Padding, Step, TermiosWhen,
+};
+
+use terminfo::Value;
+
+mod common;
+
+#[test]
+fn writes() {
+	let plan = ClearScreen::XtermClear.plan().unwrap();
+	assert_eq!(
+		plan.steps(),
+		[Step::Write(b"\x1b[H\x1b[2J\x1b[3J".to_vec())]
+	);
+	assert_eq!(plan.to_string(), "1. write \"\\e[H\\e[2J\\e[3J\"\n");
+
+	let plan = ClearScreen::Terminfo
+		.plan_with(&Options::default().database(common::database(&[])))
+		.unwrap();
+	assert_eq!(plan.steps(), [Step::Write(b"\x1b[H\x1b[J\x1b[3J".to_vec())]);
+}
+
+#[test]
+fn executes_later() {
+	for cs in [
+		ClearScreen::XtermReset,
+		ClearScreen::VtRecover,
+		ClearScreen::Terminfo,
+	] {
+		let options = Options::default().database(common::database(&[]));
+		let plan = cs.plan_with(&options).unwrap();
+
+		let mut planned = Vec::new();
+		plan.execute_to(&mut planned).unwrap();
+		let mut direct = Vec::new();
+		cs.clear_to_with(&mut direct, &options).unwrap();
+		assert_eq!(planned, direct, "{cs:?}");
+	}
+}
+
+#[test]
+fn delays() {
+	let info = common::database(&[
+		("clear", "\x1b[H\x1b[J$<200>".into()),
+		("E3", "\x1b[3J$<5/>".into()),
+		("npc", Value::True),
+	]);
+	let options = Options::default().database(info).padding(Padding::Always);
+
+	let start = Instant::now();
+	let plan = ClearScreen::Terminfo.plan_with(&options).unwrap();
+	assert!(
+		start.elapsed() < Duration::from_millis(200),
+		"slept while planning"
+	);
+	assert_eq!(
+		plan.steps(),
+		[
+			Step::Write(b"\x1b[H\x1b[J".to_vec()),
+			Step::Sleep(Duration::from_millis(200)),
+			Step::Write(b"\x1b[3J".to_vec()),
+			Step::Sleep(Duration::from_millis(5)),
+		]
+	);
+	assert_eq!(
+		plan.to_string(),
+		"1. write \"\\e[H\\e[J\"\n2. sleep 200ms\n3. write \"\\e[3J\"\n4. sleep 5ms\n"
+	);
+
+	let start = Instant::now();
+	let mut out = Vec::new();
+	plan.execute_to(&mut out).unwrap();
+	assert!(start.elapsed() >= Duration::from_millis(205));
+	assert_eq!(out, b"\x1b[H\x1b[J\x1b[3J");
+}
+
+#[test]
+fn commands() {
+	let options = Options::default().term("fixture");
+	let plan = ClearScreen::TputClear.plan_with(&options).unwrap();
+	assert_eq!(
+		plan.steps(),
+		[Step::Run {
+			argv: vec![OsString::from("tput"), OsString::from("clear")],
+			term: Some("fixture".into()),
+			capture: true,
+		}]
+	);
+	assert_eq!(
+		plan.to_string(),
+		"1. run TERM=fixture tput clear, writing its output\n"
+	);
+
+	let plan = ClearScreen::TputReset
+		.plan_with(&options.inherit_stdout(true))
+		.unwrap();
+	assert_eq!(
+		plan.to_string(),
+		"1. run TERM=fixture tput reset, with its output to stdout\n"
+	);
+}
+
+#[test]
+fn errors_when_planning() {
+	let options = Options::default().database(common::entry("empty", &[]));
+	assert!(matches!(
+		ClearScreen::TerminfoScreen.plan_with(&options),
+		Err(Error::TerminfoCapNotFound {
+			cap: "clear",
+			entry: None,
+			..
+		})
+	));
+
+	if cfg!(not(windows)) {
+		assert!(matches!(
+			ClearScreen::Cls.plan(),
+			Err(Error::CommandRejected { .. })
+		));
+	}
+
+	// restricted lookups find commands when planning
+	let nowhere = Options::default().command_lookup(CommandLookup::Dirs(Vec::new()));
+	assert!(matches!(
+		ClearScreen::TputClear.plan_with(&nowhere),
+		Err(Error::CommandRejected { .. })
+	));
+
+	// the default lookup leaves it to the platform, when the plan is executed
+	let missing = Options::default().command_path("tput", "/clearscreen/no-such-tput");
+	let plan = ClearScreen::TputClear.plan_with(&missing).unwrap();
+	assert!(matches!(
+		plan.execute_to(&mut Vec::new()),
+		Err(Error::CommandNotFound { .. })
+	));
+}
+
+#[cfg(unix)]
+#[test]
+fn termios() {
+	let plan = ClearScreen::VtWellDone.plan().unwrap();
+	let [Step::Termios(_)] = plan.steps() else {
+		panic!("{plan:?}");
+	};
+	let shown = plan.to_string();
+	assert!(
+		shown.starts_with("1. set termios: input BRKINT|"),
+		"{shown}"
+	);
+	assert!(shown.contains("local ISIG|ICANON"), "{shown}");
+	assert!(shown.ends_with("(TCSANOW, flushing input)\n"), "{shown}");
+
+	let plan = ClearScreen::VtWellDone
+		.plan_with(
+			&Options::default()
+				.termios_when(TermiosWhen::Flush)
+				.flush_input(false),
+		)
+		.unwrap();
+	assert!(plan.to_string().ends_with("(TCSAFLUSH)\n"), "{plan}");
+
+	let plan = ClearScreen::Actions(
+		ClearActions::RESTORE_TERMIOS | ClearActions::ERASE_SCREEN,
+		Backend::Xterm,
+	)
+	.plan()
+	.unwrap();
+	let [Step::Termios(_), Step::Write(bytes)] = plan.steps() else {
+		panic!("{plan:?}");
+	};
+	assert_eq!(bytes, b"\x1b[2J");
+}
+
+#[cfg(not(windows))]
+#[test]
+fn console_calls_are_windows_only() {
+	let plan = ClearScreen::WindowsCooked.plan().unwrap();
+	assert!(plan.is_empty());
+	assert_eq!(plan.to_string(), "nothing to do\n");
+
+	let plan = ClearScreen::WindowsVtClear.plan().unwrap();
+	assert_eq!(
+		plan.steps(),
+		[Step::Write(b"\x1b[H\x1b[2J\x1b[3J".to_vec())]
+	);
+}
@@ -30,6 +30,13 @@ fn terminfo_reset() {
 	}
 }
 
+#[test]
+fn terminfo_recover() {
+	if var("TERM").is_ok() && (cfg!(unix) || var("TERMINFO").is_ok()) {
+		ClearScreen::TerminfoRecover.clear().unwrap();
+	}
+}
+
 #[test]
 fn xterm_clear() {
 	ClearScreen::XtermClear.clear().unwrap();
@@ -75,6 +82,16 @@ fn vt_ris() {
 	ClearScreen::VtRis.clear().unwrap();
 }
 
+#[test]
+fn vt_recover() {
+	let mut out = Vec::new();
+	ClearScreen::VtRecover.clear_to(&mut out).unwrap();
+	assert_eq!(
+		out,
+		b"\x1b[r\x1b[?6l\x1b[?1049l\x1b[?25h\x1b[?1000;1002;1003;1006l\x1b[?2004l\x1b[?1004l\x1b[?1l\x1b>\x1b[m"
+	);
+}
+
 // TODO: test these under Win8? why don't they work
 //
 // #[test]
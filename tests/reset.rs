@@ -0,0 +1,56 @@
+//! The output of `TerminfoReset`, which should match ncurses’ `tput reset` byte for byte.
+//!
+//! The entries and what `tput reset` wrote for them are in `tests/fixtures/reset`; see the README
+//! there. Everything is in one test, as it sets `COLUMNS` to the width `tput` was run with, so the
+//! margins and tabs don’t depend on the terminal the tests are run in.
+
+use std::{env, fs, path::Path};
+
+use clearscreen::{ClearScreen, Error, Options};
+use terminfo::Database;
+
+#[test]
+fn terminfo_reset() {
+	env::set_var("COLUMNS", "20");
+
+	let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/reset");
+	// the rf and if paths in the entries are relative to the root of the repository
+	env::set_current_dir(env!("CARGO_MANIFEST_DIR")).unwrap();
+
+	let mut entries = fs::read_dir(fixtures.join("terminfo/r"))
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.collect::<Vec<_>>();
+	entries.sort();
+	assert_eq!(entries.len(), 17);
+
+	for path in entries {
+		let name = path.file_name().unwrap().to_str().unwrap().to_owned();
+		let expected = fs::read(fixtures.join("expected").join(&name)).unwrap();
+
+		let mut out = Vec::new();
+		let options = Options::default().database(Database::from_path(&path).unwrap());
+		let result = ClearScreen::TerminfoReset.clear_to_with(&mut out, &options);
+
+		if expected.is_empty() {
+			assert!(
+				matches!(
+					result,
+					Err(Error::TerminfoCapNotFound {
+						cap: "reset",
+						entry: None,
+						..
+					})
+				),
+				"{name}: {result:?}"
+			);
+		} else {
+			result.unwrap();
+			assert_eq!(
+				String::from_utf8_lossy(&out),
+				String::from_utf8_lossy(&expected),
+				"{name}"
+			);
+		}
+	}
+}
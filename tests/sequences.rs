@@ -0,0 +1,46 @@
+use clearscreen::{sequences, ClearScreen};
+
+#[test]
+fn fixed_variants() {
+	for cs in [
+		ClearScreen::XtermClear,
+		ClearScreen::XtermReset,
+		ClearScreen::VtRis,
+		ClearScreen::VtLeaveAlt,
+		ClearScreen::VtRecover,
+	] {
+		let mut out = Vec::new();
+		cs.clear_to(&mut out).unwrap();
+		assert_eq!(cs.as_bytes(), Some(&out[..]), "{cs:?}");
+		assert_eq!(cs.sequence().unwrap().to_string().as_bytes(), out, "{cs:?}");
+	}
+
+	assert_eq!(ClearScreen::VtRis.sequence(), Some(sequences::RIS));
+	assert_eq!(ClearScreen::Terminfo.as_bytes(), None);
+	assert_eq!(ClearScreen::TputClear.as_bytes(), None);
+	assert_eq!(ClearScreen::WindowsVtClear.as_bytes(), None);
+}
+
+#[test]
+fn format() {
+	assert_eq!(
+		format!("{}{}", sequences::LEAVE_ALT_SCREEN, sequences::XTERM_CLEAR),
+		"\x1b[?1049l\x1b[H\x1b[2J\x1b[3J"
+	);
+	assert_eq!(
+		format!(
+			"{}{}{}",
+			sequences::CURSOR_HOME,
+			sequences::ERASE_SCREEN,
+			sequences::ERASE_SCROLLBACK
+		),
+		sequences::XTERM_CLEAR.as_str()
+	);
+	assert!(sequences::ERASE_SCREEN
+		.as_str()
+		.starts_with(sequences::CSI.as_str()));
+	assert!(sequences::CSI.as_str().starts_with(sequences::ESC.as_str()));
+
+	let bytes: &[u8] = sequences::RIS.as_ref();
+	assert_eq!(bytes, b"\x1bc");
+}
@@ -0,0 +1,85 @@
+//! Reporting where terminfo entries come from, and where they were looked for.
+//!
+//! Everything is in one test, as it has to point the process environment at a terminfo directory.
+
+use std::{env, fs};
+
+use clearscreen::{terminfo_source, ClearScreen, Error, TerminfoEntry, TerminfoSource};
+
+#[test]
+fn terminfo_sources() {
+	let dir = env::temp_dir().join(format!("clearscreen-source-{}", std::process::id()));
+	fs::create_dir_all(dir.join("v")).unwrap();
+	fs::create_dir_all(dir.join("b")).unwrap();
+	let vt100 = dir.join("v").join("vt100");
+	fs::copy(
+		concat!(env!("CARGO_MANIFEST_DIR"), "/terminfo/v/vt100"),
+		&vt100,
+	)
+	.unwrap();
+	let broken = dir.join("b").join("broken-term");
+	fs::write(&broken, b"not a terminfo entry").unwrap();
+	env::set_var("TERMINFO", &dir);
+
+	env::set_var("TERM", "vt100");
+	assert_eq!(
+		terminfo_source().unwrap(),
+		TerminfoSource::File(vt100.clone())
+	);
+
+	// vt100 has no E3
+	match ClearScreen::TerminfoScrollback.clear_to(&mut Vec::new()) {
+		Err(Error::TerminfoCapNotFound {
+			cap,
+			term,
+			entry,
+			location,
+		}) => {
+			assert_eq!(cap, "E3");
+			assert_eq!(term, "vt100");
+			assert_eq!(entry.as_deref(), Some("vt100"));
+			assert_eq!(location, Some(TerminfoSource::File(vt100.clone())));
+		}
+		other => panic!("{other:?}"),
+	}
+
+	// falls back to the parent entry, which is readable
+	let entry = TerminfoEntry::resolve("vt100-nonexistent").unwrap();
+	assert_eq!(entry.entry, "vt100");
+	assert_eq!(entry.source, TerminfoSource::File(vt100));
+
+	match TerminfoEntry::resolve("broken-term") {
+		Err(Error::TerminfoInvalid { term, path }) => {
+			assert_eq!(term, "broken-term");
+			assert_eq!(path, broken);
+		}
+		other => panic!("{other:?}"),
+	}
+
+	env::set_var("TERM", "no-such-terminal");
+	let err = terminfo_source().unwrap_err();
+	assert!(err.to_string().contains(&*dir.to_string_lossy()), "{err}");
+	match err {
+		Error::TerminfoNotFound { searched, .. } => assert_eq!(searched.first(), Some(&dir)),
+		other => panic!("{other:?}"),
+	}
+
+	// as in ncurses: TERMINFO, then ~/.terminfo, then TERMINFO_DIRS
+	let home = dir.join("home");
+	env::set_var("HOME", &home);
+	env::set_var("TERMINFO_DIRS", "/first/terminfo::/second/terminfo");
+	match terminfo_source().unwrap_err() {
+		Error::TerminfoNotFound { searched, .. } => assert_eq!(
+			searched[..4],
+			[
+				dir.clone(),
+				home.join(".terminfo"),
+				"/first/terminfo".into(),
+				"/second/terminfo".into(),
+			]
+		),
+		other => panic!("{other:?}"),
+	}
+
+	fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,172 @@
+use std::{borrow::Cow, io::Write};
+
+use clearscreen::{
+	Backend, Capabilities, ClearActions, ClearScreen, ClearStrategy, Error, ExternalCommand,
+	Options, Registry,
+};
+
+#[derive(Debug)]
+struct AcmeClear;
+
+impl ClearStrategy for AcmeClear {
+	fn name(&self) -> Cow<'_, str> {
+		"AcmeClear".into()
+	}
+
+	fn capabilities(&self) -> Capabilities {
+		Capabilities::new(ClearActions::ERASE_SCREEN)
+	}
+
+	fn handles_term(&self, term: &str) -> bool {
+		term.starts_with("acme")
+	}
+
+	fn clear_to_with(&self, w: &mut dyn Write, _options: &Options) -> Result<(), Error> {
+		w.write_all(b"acme")?;
+		Ok(())
+	}
+}
+
+/// Stands in for a built-in strategy by using its name.
+#[derive(Debug)]
+struct QuietXterm;
+
+impl ClearStrategy for QuietXterm {
+	fn name(&self) -> Cow<'_, str> {
+		"XtermClear".into()
+	}
+
+	fn capabilities(&self) -> Capabilities {
+		Capabilities::default()
+	}
+
+	fn clear_to_with(&self, _w: &mut dyn Write, _options: &Options) -> Result<(), Error> {
+		Ok(())
+	}
+}
+
+fn clear_to(strategy: &dyn ClearStrategy) -> Vec<u8> {
+	let mut out = Vec::new();
+	strategy.clear_to(&mut out).unwrap();
+	out
+}
+
+#[test]
+fn builtin() {
+	let registry = Registry::new();
+	let names = registry.names();
+	assert_eq!(names.first().map(String::as_str), Some("Terminfo"));
+	assert!(names.iter().any(|name| name == "VtWellDone"));
+	assert!(!names
+		.iter()
+		.any(|name| name.starts_with("TerminfoCapability")));
+
+	let xterm = registry.get("XtermClear").unwrap();
+	assert_eq!(clear_to(&*xterm), b"\x1b[H\x1b[2J\x1b[3J");
+	assert!(registry.get("NoSuchStrategy").is_none());
+	assert!(Registry::empty().get("XtermClear").is_none());
+}
+
+#[test]
+fn custom() {
+	let mut registry = Registry::new();
+	registry.register(AcmeClear).register(QuietXterm);
+
+	assert_eq!(clear_to(&*registry.get("AcmeClear").unwrap()), b"acme");
+	assert_eq!(clear_to(&*registry.get("XtermClear").unwrap()), b"");
+	assert_eq!(
+		registry
+			.names()
+			.iter()
+			.filter(|name| *name == "XtermClear")
+			.count(),
+		1
+	);
+	assert_eq!(
+		registry.names().last().map(String::as_str),
+		Some("AcmeClear")
+	);
+}
+
+#[test]
+fn parameters_distinguish() {
+	let mut registry = Registry::empty();
+	registry
+		.register(ClearScreen::TerminfoCapability("smcup"))
+		.register(ClearScreen::TerminfoCapability("rmcup"))
+		.register(ClearScreen::Actions(
+			ClearActions::ERASE_SCREEN,
+			Backend::Xterm,
+		))
+		.register(ClearScreen::Actions(
+			ClearActions::HOME_CURSOR,
+			Backend::Xterm,
+		));
+
+	assert_eq!(registry.names().len(), 4);
+	assert_eq!(
+		clear_to(&*registry.get("Actions(ERASE_SCREEN, Xterm)").unwrap()),
+		b"\x1b[2J"
+	);
+	assert_eq!(
+		clear_to(&*registry.get("Actions(HOME_CURSOR, Xterm)").unwrap()),
+		b"\x1b[H"
+	);
+	assert_eq!(
+		registry.get("TerminfoCapability(smcup)").unwrap().name(),
+		"TerminfoCapability(smcup)"
+	);
+}
+
+#[test]
+fn selection() {
+	let mut registry = Registry::empty();
+	registry.register(AcmeClear);
+
+	assert_eq!(registry.for_term("acme-256color").name(), "AcmeClear");
+	assert_eq!(
+		registry.for_term("tmux-256color").name(),
+		ClearScreen::for_term("tmux-256color").name()
+	);
+}
+
+#[test]
+fn names() {
+	assert_eq!(ClearScreen::VtRis.name(), "VtRis");
+	assert_eq!(
+		ClearScreen::TerminfoCapability("sgr0").name(),
+		"TerminfoCapability(sgr0)"
+	);
+	assert_eq!(
+		ClearScreen::Actions(
+			ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN,
+			Backend::Xterm
+		)
+		.name(),
+		"Actions(HOME_CURSOR | ERASE_SCREEN, Xterm)"
+	);
+	assert_eq!(ExternalCommand::setterm().name(), "setterm");
+}
+
+#[test]
+fn capabilities() {
+	let caps = ClearScreen::Terminfo.capabilities();
+	assert_eq!(
+		caps.actions,
+		ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN | ClearActions::ERASE_SCROLLBACK
+	);
+	assert!(caps.terminfo);
+	assert!(!caps.command);
+
+	let caps = ClearScreen::TputReset.capabilities();
+	assert_eq!(caps.actions, ClearActions::FULL_RESET);
+	assert!(!caps.terminfo);
+	assert!(caps.command);
+
+	assert!(ClearScreen::Cls.capabilities().command);
+	assert_eq!(
+		ClearScreen::VtRis.capabilities().actions,
+		ClearActions::FULL_RESET
+	);
+	assert!(ExternalCommand::setterm().capabilities().command);
+}
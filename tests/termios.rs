@@ -0,0 +1,148 @@
+#![cfg(unix)]
+
+use clearscreen::{
+	ClearScreen, InputFlags, LocalFlags, Options, OutputFlags, SpecialCharacterIndices, Step,
+	TermiosProfile, TermiosState, TermiosWhen, NCCS,
+};
+
+fn stty_string(flags: &str, cc: u8) -> String {
+	let mut s = flags.to_string();
+	for _ in 0..NCCS {
+		s.push_str(&format!(":{cc:x}"));
+	}
+	s
+}
+
+#[test]
+fn stty_roundtrip() {
+	let s = stty_string("500:5:bf:8a3b", 0x1c);
+	let state: TermiosState = s.parse().unwrap();
+	assert_eq!(state.to_string(), s);
+}
+
+#[test]
+fn stty_trailing_newline() {
+	let s = stty_string("6b02:3:4b00:200005cf", 0x7f);
+	let state: TermiosState = format!("{s}\n").parse().unwrap();
+	assert_eq!(state.to_string(), s);
+}
+
+#[test]
+fn stty_wrong_field_count() {
+	assert!("500:5:bf:8a3b:3:1c".parse::<TermiosState>().is_err());
+}
+
+#[test]
+fn stty_invalid_hex() {
+	let s = stty_string("500:5:zz:8a3b", 0);
+	assert!(s.parse::<TermiosState>().is_err());
+}
+
+#[test]
+fn stty_cc_out_of_range() {
+	let s = stty_string("500:5:bf:8a3b", 0).replacen(":0", ":100", 1);
+	assert!(s.parse::<TermiosState>().is_err());
+}
+
+#[test]
+fn profile_sets_only_its_flags() {
+	let state: TermiosState = stty_string("500:5:bf:8a3b", 0x1c).parse().unwrap();
+	let applied = TermiosProfile::new()
+		.with_input(InputFlags::ICRNL | InputFlags::IXON)
+		.with_local(LocalFlags::ICANON)
+		.applied_to(&state);
+
+	assert_eq!(applied.input_flags, InputFlags::ICRNL | InputFlags::IXON);
+	assert_eq!(applied.output_flags, OutputFlags::empty());
+	assert_eq!(applied.local_flags, LocalFlags::ICANON);
+	assert_eq!(applied.control_chars, state.control_chars);
+}
+
+#[test]
+fn profile_builds_on_predefined() {
+	let state: TermiosState = stty_string("0:0:0:0", 0).parse().unwrap();
+	let applied = TermiosProfile::cooked()
+		.with_output(OutputFlags::ONLCR)
+		.without_input(InputFlags::ISTRIP)
+		.applied_to(&state);
+
+	assert!(applied
+		.output_flags
+		.contains(OutputFlags::OPOST | OutputFlags::ONLCR));
+	assert!(applied.input_flags.contains(InputFlags::ICRNL));
+	assert!(!applied.input_flags.contains(InputFlags::ISTRIP));
+}
+
+#[test]
+fn profile_sets_control_chars() {
+	let state: TermiosState = stty_string("500:5:bf:8a3b", 0x1c).parse().unwrap();
+	let applied = TermiosProfile::new()
+		.cc(SpecialCharacterIndices::VINTR, 0x01)
+		.cc(SpecialCharacterIndices::VERASE, 0x08)
+		.cc(SpecialCharacterIndices::VINTR, 0x03)
+		.applied_to(&state);
+
+	for (index, cc) in applied.control_chars.iter().enumerate() {
+		let expected = if index == SpecialCharacterIndices::VINTR as usize {
+			0x03
+		} else if index == SpecialCharacterIndices::VERASE as usize {
+			0x08
+		} else {
+			0x1c
+		};
+		assert_eq!(*cc, expected, "control character {index}");
+	}
+}
+
+/// The profile that the variant would apply with the options.
+fn planned_profile(cs: ClearScreen, options: &Options) -> TermiosProfile {
+	let plan = cs.plan_with(options).unwrap();
+	match plan.steps() {
+		[Step::Termios(profile)] => profile.clone(),
+		steps => panic!("{steps:?}"),
+	}
+}
+
+#[test]
+fn when_defaults() {
+	let options = Options::default();
+	assert_eq!(
+		planned_profile(ClearScreen::VtCooked, &options),
+		TermiosProfile::cooked()
+			.when(TermiosWhen::Now)
+			.flush_input(false)
+	);
+	assert_eq!(
+		planned_profile(ClearScreen::VtWellDone, &options),
+		TermiosProfile::well_done()
+			.when(TermiosWhen::Now)
+			.flush_input(true)
+	);
+}
+
+#[test]
+fn when_overridden() {
+	let options = Options::default()
+		.termios_when(TermiosWhen::Drain)
+		.flush_input(false);
+	assert_eq!(
+		planned_profile(ClearScreen::VtWellDone, &options),
+		TermiosProfile::well_done()
+			.when(TermiosWhen::Drain)
+			.flush_input(false)
+	);
+
+	let options = Options::default().termios_when(TermiosWhen::Flush);
+	assert_eq!(
+		planned_profile(ClearScreen::VtWellDone, &options),
+		TermiosProfile::well_done()
+			.when(TermiosWhen::Flush)
+			.flush_input(true)
+	);
+	assert_eq!(
+		planned_profile(ClearScreen::VtCooked, &Options::default().flush_input(true)),
+		TermiosProfile::cooked()
+			.when(TermiosWhen::Now)
+			.flush_input(true)
+	);
+}
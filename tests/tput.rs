@@ -0,0 +1,77 @@
+//! These need `tput` from ncurses, and are skipped without it.
+
+use std::process::Command;
+
+use clearscreen::{Backend, ClearActions, ClearScreen, Options};
+
+fn has_tput() -> bool {
+	Command::new("tput").arg("-V").output().is_ok()
+}
+
+#[test]
+fn captures_output() {
+	if !has_tput() {
+		return;
+	}
+
+	let mut out = Vec::new();
+	ClearScreen::TputClear
+		.clear_to_with(&mut out, &Options::default().term("xterm"))
+		.unwrap();
+	assert!(out.starts_with(b"\x1b[H\x1b[2J"), "{out:?}");
+}
+
+#[test]
+fn passes_term() {
+	if !has_tput() {
+		return;
+	}
+
+	let mut out = Vec::new();
+	ClearScreen::TputClear
+		.clear_to_with(&mut out, &Options::default().term("ansi"))
+		.unwrap();
+	assert_eq!(out, b"\x1b[H\x1b[J");
+}
+
+#[test]
+fn unknown_term() {
+	if !has_tput() {
+		return;
+	}
+
+	let mut out = Vec::new();
+	assert!(ClearScreen::TputClear
+		.clear_to_with(&mut out, &Options::default().term("no-such-terminal"))
+		.is_err());
+	assert!(out.is_empty());
+}
+
+#[test]
+fn actions() {
+	if !has_tput() {
+		return;
+	}
+
+	let options = Options::default().term("xterm");
+	let clear = |actions| {
+		let mut out = Vec::new();
+		ClearScreen::Actions(actions, Backend::Tput)
+			.clear_to_with(&mut out, &options)
+			.unwrap();
+		out
+	};
+
+	let mut out = Vec::new();
+	ClearScreen::TputClear
+		.clear_to_with(&mut out, &options)
+		.unwrap();
+	let (all, _) = ClearScreen::TputClear.actions().unwrap();
+	assert_eq!(clear(all), out);
+
+	assert_eq!(
+		clear(ClearActions::HOME_CURSOR | ClearActions::ERASE_SCREEN),
+		b"\x1b[H\x1b[2J"
+	);
+	assert_eq!(clear(ClearActions::ERASE_SCROLLBACK), b"\x1b[3J");
+}